@@ -1,54 +1,147 @@
 use crate::{
-    check_program_version, conditional_join,
+    append_history, badge_prefs, cache_universal_url, cached_universal_url, check_program_version,
+    color_enabled, conditional_join, confirm, exit_on_remote, group_thousands,
     models::{
-        badge_options::BadgePreferences,
-        cli::{Mod, SetArgs, Workflow},
+        badge_options::{BadgeFormat, BadgePreferences},
+        cli::{AddArgs, Backend, Cli, DumpFormat, Mod, OutputMode, SetArgs, Workflow},
         error::Error,
-        json_data::Input,
+        json_data::{Author, Input, Output, RepositoryPublicKey, Snapshot},
     },
-    read,
+    print_badges, print_deltas, prompt, read, read_badge_prefs,
     services::{
         git::{
-            create_remote, delete_cache_by_key, get_public_key, set_repository_secret,
-            set_repository_variable, set_workflow_state, update_remote,
+            delete_cache_by_key, delete_caches_by_prefix, get_public_key, get_workflow_status,
+            gist_file_name, set_repository_secret, set_repository_variable, set_workflow_state,
+            verify_actions_permissions,
         },
-        nexus::update_download_counts,
+        nexus::{get_author_mods, get_mod_details, update_download_counts, validate_key, Config},
+        remote::RemoteStore,
     },
-    verify_gist, verify_git, verify_repo, verify_repo_from, write, write_badges, StartupVars,
-    ENV_NAME_GIST_ID, ENV_NAME_GIT, ENV_NAME_MODS, ENV_NAME_NEXUS, PATHS, VARS,
+    set_badge_prefs, signed_diff, updated_badge_enabled, utc_date_time, verify_gist, verify_git,
+    verify_remote_token, verify_repo, verify_repo_from, write, write_actions_summary, write_badges,
+    StartupVars, ENV_NAME_BACKEND, ENV_NAME_ENDPOINT_TOKEN, ENV_NAME_ENDPOINT_URL,
+    ENV_NAME_GIST_FILE, ENV_NAME_GIST_ID, ENV_NAME_GIT, ENV_NAME_GITLAB, ENV_NAME_GITLAB_API_URL,
+    ENV_NAME_MODS, ENV_NAME_NEXUS, ENV_NAME_UPDATED_BADGE, PATHS, SECS_PER_DAY, TOTAL_KEY, VARS,
+    VERSION_CHECK_TIMEOUT,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use clap::CommandFactory;
+use clap_complete::Shell;
+use log::{info, warn};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    io::{self, ErrorKind},
+    path::Path,
 };
-use std::io::{self, ErrorKind};
 
-pub async fn version(on_remote: bool) -> reqwest::Result<()> {
-    let ver_res = check_program_version().await;
+/// On local the current version is printed before the check for a new version is awaited, the
+/// check is abandoned after [`VERSION_CHECK_TIMEOUT`]. On remote the check is always awaited so the
+/// exit code reflects its result
+pub async fn version(on_remote: bool, check: bool, mode: OutputMode) -> reqwest::Result<()> {
+    if !check {
+        if mode.is_json() {
+            println!(
+                "{}",
+                serde_json::json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION") })
+            );
+        } else {
+            println!("nexus_mods v{}", env!("CARGO_PKG_VERSION"));
+        }
+        exit_on_remote(on_remote, 0);
+        return Ok(());
+    }
     if on_remote {
-        match ver_res {
-            Ok(Some(_)) => {
-                println!("New Nexus Badges version available");
-                std::process::exit(70)
-            }
-            Ok(None) => {
-                println!("Nexus Badges up to date");
-                std::process::exit(0)
-            }
+        let (msg, code) = match check_program_version(true).await {
+            Ok(Some(_)) => ("New Nexus Badges version available", 70),
+            Ok(None) => ("Nexus Badges up to date", 0),
             Err(err) => {
                 eprintln!("{err}");
                 std::process::exit(20)
             }
+        };
+        if mode.is_json() {
+            println!(
+                "{}",
+                serde_json::json!({ "status": "ok", "update_available": code == 70 })
+            );
+        } else {
+            println!("{msg}");
         }
+        std::process::exit(code)
     }
-    println!("nexus_mods v{}", env!("CARGO_PKG_VERSION"));
-    if let Some(msg) = ver_res? {
-        println!("{msg}")
+    if !mode.is_json() {
+        println!("nexus_mods v{}", env!("CARGO_PKG_VERSION"));
+    }
+    let update_msg =
+        match tokio::time::timeout(VERSION_CHECK_TIMEOUT, check_program_version(true)).await {
+            Ok(res) => Some(res?),
+            Err(_) => None,
+        };
+    if mode.is_json() {
+        println!(
+            "{}",
+            serde_json::json!({
+                "status": "ok",
+                "version": env!("CARGO_PKG_VERSION"),
+                "update_available": update_msg.as_ref().map(Option::is_some),
+                "message": update_msg.flatten(),
+            })
+        );
+        return Ok(());
+    }
+    match update_msg {
+        Some(Some(msg)) => println!("{msg}"),
+        Some(None) => (),
+        None => warn!("Timed out checking for a new version"),
     }
     Ok(())
 }
 
+pub fn print_completions(shell: Shell) {
+    let mut cmd = Cli::command();
+    let bin_name = cmd.get_name().to_string();
+    clap_complete::generate(shell, &mut cmd, bin_name, &mut io::stdout());
+}
+
+pub fn write_manpages(out: Option<&Path>) -> io::Result<()> {
+    let cmd = Cli::command();
+    match out {
+        Some(dir) => {
+            std::fs::create_dir_all(dir)?;
+            clap_mangen::generate_to(cmd, dir)?;
+            info!("Man pages saved to: {}", dir.display());
+            Ok(())
+        }
+        None => clap_mangen::Man::new(cmd).render(&mut io::stdout()),
+    }
+}
+
 pub trait Modify {
-    fn add_mod(self, details: Mod) -> impl std::future::Future<Output = Result<(), Error>> + Send;
+    fn add_mod(
+        self,
+        details: Mod,
+        mode: OutputMode,
+    ) -> impl std::future::Future<Output = Result<(), Error>> + Send;
+    fn add_batch(
+        self,
+        args: AddArgs,
+        mode: OutputMode,
+    ) -> impl std::future::Future<Output = Result<(), Error>> + Send;
+    fn add_author(
+        self,
+        author: Author,
+        mode: OutputMode,
+    ) -> impl std::future::Future<Output = Result<(), Error>> + Send;
     fn remove_mod(
         self,
         details: Mod,
+        mode: OutputMode,
+    ) -> impl std::future::Future<Output = Result<(), Error>> + Send;
+    fn import_mods(
+        self,
+        path: &Path,
+        merge: bool,
     ) -> impl std::future::Future<Output = Result<(), Error>> + Send;
 }
 
@@ -60,15 +153,22 @@ trait Update {
 
 impl Update for Vec<Mod> {
     async fn write_and_try_set_remote(self) -> Result<(), Error> {
-        let new_mod_json = verify_repo()
-            .is_ok()
-            .then(|| serde_json::to_string(&self).expect("`Vec<Mod>` is always ok to stringify"));
-        let updated = Input::from(VARS.get().expect("set on startup"), self);
-        write(updated, &PATHS.input)?;
+        Input::from(VARS.get().expect("set on startup"), self)
+            .write_and_try_set_remote()
+            .await
+    }
+}
+
+impl Update for Input {
+    async fn write_and_try_set_remote(self) -> Result<(), Error> {
+        let new_mod_json = verify_repo().is_ok().then(|| {
+            serde_json::to_string(&self.mods).expect("`Vec<Mod>` is always ok to stringify")
+        });
+        write(self, &PATHS.input)?;
 
         if let Some(new_variable) = new_mod_json {
             if let Err(err) = set_repository_variable(ENV_NAME_MODS, &new_variable).await {
-                println!("{} updated locally", PATHS.input);
+                info!("{} updated locally", PATHS.input);
                 return Err(err);
             }
         }
@@ -78,21 +178,139 @@ impl Update for Vec<Mod> {
 }
 
 impl Modify for Vec<Mod> {
-    async fn add_mod(mut self, details: Mod) -> Result<(), Error> {
+    async fn add_mod(mut self, details: Mod, mode: OutputMode) -> Result<(), Error> {
         if self.contains(&details) {
             return Err(Error::Io(io::Error::new(
                 ErrorKind::InvalidInput,
                 format!("Mod already exists in: {}", PATHS.input),
             )));
         }
+        match verify_exists(&details, mode).await {
+            Err(err @ Error::NotFound(_)) => return Err(err),
+            Err(err) => warn_unverified(&err),
+            Ok(()) => (),
+        }
+        let mod_json = mode.is_json().then(|| mod_summary(&details));
         self.push(details);
+        let mod_ct = self.len();
         self.write_and_try_set_remote().await?;
 
-        println!("Mod Registered!");
+        match mod_json {
+            Some(added) => println!(
+                "{}",
+                serde_json::json!({ "status": "ok", "added": added, "mods": mod_ct })
+            ),
+            None => info!("Mod Registered!"),
+        }
+        Ok(())
+    }
+
+    async fn add_batch(mut self, args: AddArgs, mode: OutputMode) -> Result<(), Error> {
+        let mut batch = args.batch;
+        if let Some(path) = args.from_file {
+            batch.extend(read_mod_list(&path)?);
+        }
+
+        let (mut added, mut skipped) = (Vec::new(), Vec::new());
+        let mut warned = false;
+
+        for details in batch {
+            if self.contains(&details) {
+                if !mode.is_json() {
+                    info!("Skipped: {}, already registered", details.url());
+                }
+                skipped.push(mod_summary(&details));
+                continue;
+            }
+            match verify_exists(&details, mode).await {
+                Err(err @ Error::NotFound(_)) => {
+                    warn!("{err}");
+                    skipped.push(mod_summary(&details));
+                    continue;
+                }
+                Err(err) if !warned => {
+                    warn_unverified(&err);
+                    warned = matches!(err, Error::Missing(_));
+                }
+                _ => (),
+            }
+            added.push(mod_summary(&details));
+            self.push(details);
+        }
+
+        if added.is_empty() {
+            return Err(Error::Io(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("No new mods to register, {} mod(s) skipped", skipped.len()),
+            )));
+        }
+
+        let mod_ct = self.len();
+        self.write_and_try_set_remote().await?;
+
+        if mode.is_json() {
+            println!(
+                "{}",
+                serde_json::json!({
+                    "status": "ok",
+                    "added": added,
+                    "skipped": skipped,
+                    "mods": mod_ct,
+                })
+            );
+        } else {
+            info!(
+                "{} mod(s) registered, {} mod(s) skipped",
+                added.len(),
+                skipped.len()
+            );
+        }
+        Ok(())
+    }
+
+    async fn add_author(mut self, author: Author, mode: OutputMode) -> Result<(), Error> {
+        let (added, skipped) = register_author_mods(&mut self, &author).await?;
+
+        let mut input = Input::from(VARS.get().expect("set on startup"), self);
+        let new_author = !input.authors.contains(&author);
+        if added.is_empty() && !new_author {
+            return Err(Error::Io(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "No new mods by {} to register, {skipped} mod(s) already registered",
+                    author.name
+                ),
+            )));
+        }
+        if new_author {
+            input.authors.push(author.clone());
+        }
+        let mod_ct = input.mods.len();
+        input.write_and_try_set_remote().await?;
+
+        if mode.is_json() {
+            println!(
+                "{}",
+                serde_json::json!({
+                    "status": "ok",
+                    "author": author.name,
+                    "domain": author.domain,
+                    "added": added,
+                    "skipped": skipped,
+                    "mods": mod_ct,
+                })
+            );
+        } else {
+            info!(
+                "{} mod(s) by {} registered, {skipped} mod(s) already registered",
+                added.len(),
+                author.name
+            );
+        }
         Ok(())
     }
 
-    async fn remove_mod(mut self, details: Mod) -> Result<(), Error> {
+    async fn remove_mod(mut self, details: Mod, mode: OutputMode) -> Result<(), Error> {
         let i = self
             .iter()
             .position(|mod_details| *mod_details == details)
@@ -102,12 +320,348 @@ impl Modify for Vec<Mod> {
                     format!("Mod does not exist in: {}", PATHS.input),
                 ))
             })?;
-        self.swap_remove(i);
+        let removed = self.swap_remove(i);
+        let mod_ct = self.len();
         self.write_and_try_set_remote().await?;
 
-        println!("Mod removed!");
+        if mode.is_json() {
+            println!(
+                "{}",
+                serde_json::json!({
+                    "status": "ok",
+                    "removed": mod_summary(&removed),
+                    "mods": mod_ct,
+                })
+            );
+        } else {
+            info!("Mod removed!");
+        }
         Ok(())
     }
+
+    async fn import_mods(mut self, path: &Path, merge: bool) -> Result<(), Error> {
+        let imported = read::<Vec<Mod>>(&path.to_string_lossy())?;
+        let mut skipped = 0;
+
+        if merge {
+            for details in imported {
+                if self.contains(&details) {
+                    skipped += 1;
+                } else {
+                    self.push(details);
+                }
+            }
+        } else {
+            self = imported;
+        }
+
+        let mod_ct = self.len();
+        self.write_and_try_set_remote().await?;
+
+        if skipped != 0 {
+            info!("Skipped {skipped} mod(s) that were already registered");
+        }
+        info!("Mods imported! {mod_ct} mod(s) registered");
+        Ok(())
+    }
+}
+
+/// Registers each mod `author` has uploaded that is not yet tracked. Returns a summary of each
+/// added mod and the number of mods that were already registered
+async fn register_author_mods(
+    mods: &mut Vec<Mod>,
+    author: &Author,
+) -> Result<(Vec<serde_json::Value>, usize), Error> {
+    let mod_ids = get_author_mods(&Config::from_startup(), &author.domain, &author.name).await?;
+    if mod_ids.is_empty() {
+        return Err(Error::Io(io::Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "No mods uploaded by {} were found for: {}",
+                author.name, author.domain
+            ),
+        )));
+    }
+
+    let (mut added, mut skipped) = (Vec::new(), 0);
+    for mod_id in mod_ids {
+        let details = Mod {
+            domain: author.domain.clone(),
+            mod_id,
+            label: None,
+            key: None,
+        };
+        if mods.contains(&details) {
+            skipped += 1;
+            continue;
+        }
+        added.push(mod_summary(&details));
+        mods.push(details);
+    }
+    Ok((added, skipped))
+}
+
+fn warn_unverified(err: &Error) {
+    match err {
+        Error::Missing(msg) => warn!("{msg}"),
+        err => warn!("Could not verify mod exists on Nexus, {err}"),
+    }
+}
+
+/// Returns `Error::NotFound` if Nexus reports the mod does not exist, any other error means the
+/// mod could not be verified
+async fn verify_exists(details: &Mod, mode: OutputMode) -> Result<(), Error> {
+    match get_mod_details(&Config::from_startup(), details).await {
+        Some(Ok(info)) => {
+            if !mode.is_json() {
+                info!("Found: {}", info.name)
+            }
+            Ok(())
+        }
+        Some(Err(err @ Error::NotFound(_))) => Err(err),
+        Some(Err(err)) => Err(err),
+        None => Err(Error::Missing(
+            "Nexus api key missing, could not verify mod exists on Nexus. \
+            Use command 'set' to store private key",
+        )),
+    }
+}
+
+/// Reads `domain:mod_id` pairs, one per line. Blank lines and lines starting with `#` are ignored
+fn read_mod_list(path: &Path) -> Result<Vec<Mod>, Error> {
+    std::fs::read_to_string(path)?
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_num, line)| {
+            line.parse::<Mod>().map_err(|err| {
+                Error::Io(io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("{}, line {line_num}: {err}", path.display()),
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Swaps the input file with its backup, the replaced contents become the new backup so a restore
+/// can itself be undone
+pub fn restore_input() -> Result<(), Error> {
+    let (input, backup) = (PATHS.input.as_ref(), PATHS.input_backup());
+    if !Path::new(&backup).exists() {
+        return Err(Error::Io(io::Error::new(
+            ErrorKind::NotFound,
+            format!("No backup of {input} exists"),
+        )));
+    }
+
+    let temp_path = format!("{input}.tmp");
+    let has_input = Path::new(input).exists();
+    if has_input {
+        std::fs::rename(input, &temp_path)?;
+    }
+    std::fs::rename(&backup, input)?;
+    if has_input {
+        std::fs::rename(&temp_path, &backup)?;
+    }
+
+    info!("Restored {input} from: {backup}");
+    Ok(())
+}
+
+pub fn export_mods(mods: &[Mod], out: &Path) -> Result<(), Error> {
+    write(mods, &out.to_string_lossy())?;
+    info!("Exported {} mod(s) to: {}", mods.len(), out.display());
+    Ok(())
+}
+
+pub async fn dump_counts(
+    mods: Vec<Mod>,
+    format: DumpFormat,
+    out: Option<&Path>,
+) -> Result<(), Error> {
+    let output = update_download_counts(mods, true).await?;
+    let contents = match format {
+        DumpFormat::Json => serde_json::to_string_pretty(&output)?,
+        DumpFormat::Csv => {
+            let mut csv = String::from("name,uid,downloads,unique,url\n");
+            for details in output.mods.values().filter(|details| details.uid != 0) {
+                csv.push_str(&format!(
+                    "{},{},{},{},{}\n",
+                    csv_field(&details.name),
+                    details.uid,
+                    details.mod_downloads,
+                    details.mod_unique_downloads,
+                    csv_field(&details.url)
+                ));
+            }
+            csv
+        }
+    };
+
+    match out {
+        Some(path) => {
+            std::fs::write(path, contents)?;
+            info!("Download counts saved to: {}", path.display());
+        }
+        None => print!("{contents}"),
+    }
+    Ok(())
+}
+
+/// Quotes `field` when it contains characters that would break a csv row
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        return Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")));
+    }
+    Cow::Borrowed(field)
+}
+
+/// Prints the growth of each entry between the snapshot closest to `since` and the latest snapshot
+/// in 'history.json'. Mod names are looked up in the local output file, falling back to the key
+pub fn print_stats(since: Option<u64>, mode: OutputMode) -> Result<(), Error> {
+    let history = match read::<Vec<Snapshot>>(&PATHS.history) {
+        Ok(history) => history,
+        Err(Error::Io(err)) if err.kind() == ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(err),
+    };
+
+    let Some((latest, prior)) = history.split_last().filter(|(_, prior)| !prior.is_empty()) else {
+        return Err(Error::NotSetup(
+            "At least two download count snapshots are required, a snapshot is saved to \
+            'history.json' each time counts are updated locally",
+        ));
+    };
+
+    let baseline = match since {
+        Some(since) => prior
+            .iter()
+            .min_by_key(|snapshot| snapshot.timestamp.abs_diff(since)),
+        None => prior.first(),
+    }
+    .expect("`prior` is not empty");
+
+    let names = read::<Output>(&PATHS.output).map(|output| output.mods).ok();
+    let name_of = |key: &str| {
+        names
+            .as_ref()
+            .and_then(|names| names.get(key))
+            .map_or(key, |entry| entry.name.as_str())
+            .to_string()
+    };
+
+    let days = latest.timestamp.saturating_sub(baseline.timestamp) as f64 / SECS_PER_DAY as f64;
+    let total_downloads = |snapshot: &Snapshot| {
+        snapshot
+            .counts
+            .get(TOTAL_KEY)
+            .map_or(0, |counts| counts.downloads)
+    };
+    let (sign, total) = signed_diff(total_downloads(baseline), total_downloads(latest));
+    let per_day = if days > 0.0 { total as f64 / days } else { 0.0 };
+
+    let mods = latest
+        .counts
+        .iter()
+        .filter(|(key, _)| *key != TOTAL_KEY)
+        .map(|(key, counts)| {
+            let prev = baseline.counts.get(key).map(|prev| prev.downloads);
+            (key, name_of(key), prev, counts.downloads)
+        })
+        .collect::<Vec<_>>();
+
+    if mode.is_json() {
+        let signed = |sign: char, n: usize| if sign == '-' { -(n as i64) } else { n as i64 };
+        println!(
+            "{}",
+            serde_json::json!({
+                "status": "ok",
+                "from": utc_date_time(baseline.timestamp),
+                "to": utc_date_time(latest.timestamp),
+                "days": days,
+                "mods": mods.iter().map(|(key, name, prev, curr)| serde_json::json!({
+                    "key": key,
+                    "name": name,
+                    "downloads": curr,
+                    "growth": prev.map(|prev| {
+                        let (sign, diff) = signed_diff(prev, *curr);
+                        signed(sign, diff)
+                    }),
+                })).collect::<Vec<_>>(),
+                "total_growth": signed(sign, total),
+                "per_day": if sign == '-' { -per_day } else { per_day },
+            })
+        );
+        return Ok(());
+    }
+
+    println!(
+        "Download growth from {} to {} ({days:.1} days):",
+        utc_date_time(baseline.timestamp),
+        utc_date_time(latest.timestamp)
+    );
+    for (_, name, prev, curr) in mods {
+        let Some(prev) = prev else {
+            println!("- {name}: {} (not tracked at start)", group_thousands(curr));
+            continue;
+        };
+        let (sign, diff) = signed_diff(prev, curr);
+        print!("- {name}: {sign}{}", group_thousands(diff));
+        if prev != 0 {
+            print!(" ({sign}{:.1}%)", diff as f64 / prev as f64 * 100.0);
+        }
+        println!();
+    }
+    println!(
+        "Total: {sign}{}, {sign}{per_day:.1} per day on average",
+        group_thousands(total)
+    );
+    Ok(())
+}
+
+fn mod_summary(details: &Mod) -> serde_json::Value {
+    let mut summary = serde_json::json!({ "domain": details.domain, "mod_id": details.mod_id });
+    if let Some(ref key) = details.key {
+        summary["key"] = key.as_str().into();
+    }
+    summary
+}
+
+pub fn list_mods(mods: &[Mod], mode: OutputMode) {
+    if mode.is_json() {
+        println!(
+            "{}",
+            serde_json::json!({
+                "status": "ok",
+                "mods": mods.iter().map(|details| {
+                    let mut summary = mod_summary(details);
+                    if let Some(ref label) = details.label {
+                        summary["label"] = label.as_str().into();
+                    }
+                    summary["url"] = details.url().into();
+                    summary
+                }).collect::<Vec<_>>(),
+            })
+        );
+        return;
+    }
+    if mods.is_empty() {
+        println!("No mods registered, use the command 'add' to register a mod");
+        return;
+    }
+    println!("Tracked mods:");
+    for (i, details) in mods.iter().enumerate() {
+        print!("{}. {}, mod_id: {}", i + 1, details.domain, details.mod_id);
+        if let Some(ref label) = details.label {
+            print!(", label: {label}");
+        }
+        if let Some(ref key) = details.key {
+            print!(", key: {key}");
+        }
+        println!("\n   {}", details.url());
+    }
+    println!("{} mod(s) registered", mods.len());
 }
 
 macro_rules! propagate_err {
@@ -134,6 +688,36 @@ impl Input {
             from.modified.gist_id = true;
             std::mem::swap(&mut self.gist_id, id);
         }
+        if let Some(ref mut file) = from.gist_file {
+            from.modified.gist_file = true;
+            self.gist_file = if file.eq_ignore_ascii_case("default") {
+                String::new()
+            } else {
+                std::mem::take(file)
+            };
+        }
+        if let Some(public) = from.public_gist {
+            modified = true;
+            self.gist_public = public;
+            if public {
+                warn!(
+                    "Remote gist will be created as public, anyone with the link can view the \
+                    download counts. Credentials are never stored in the gist"
+                );
+            } else {
+                info!("Remote gist will be created as private");
+            }
+            if !self.gist_id.is_empty() {
+                warn!("Existing gist is unchanged, use command 'init' to create a new gist");
+            }
+        }
+        if let Some(ref mut desc) = from.gist_desc {
+            modified = true;
+            self.gist_description = std::mem::take(desc);
+            if !self.gist_id.is_empty() {
+                info!("Gist description will be updated the next time the remote gist is updated");
+            }
+        }
         if let Some(ref mut repo) = from.repo {
             modified = true;
             self.repo = std::mem::take(repo);
@@ -142,6 +726,48 @@ impl Input {
             modified = true;
             self.owner = std::mem::take(owner);
         }
+        if let Some(ref mut url) = from.git_base_url {
+            modified = true;
+            self.git_base_url = std::mem::take(url);
+        }
+        if let Some(backend) = from.backend {
+            from.modified.backend = true;
+            self.backend = backend;
+            match backend {
+                Backend::Gitlab => warn!(
+                    "GitLab snippets are always created as public, anyone with the link can view \
+                    the download counts. Credentials are never stored in the snippet"
+                ),
+                Backend::Http => info!(
+                    "Counts will be PUT to the stored endpoint url, the endpoint must be publicly \
+                    readable for shields.io to display the badges"
+                ),
+                Backend::Github => (),
+            }
+            if !self.gist_id.is_empty() {
+                warn!("Existing remote is unchanged, use command 'init' to create a new remote");
+            }
+        }
+        if let Some(ref mut token) = from.gitlab_token {
+            from.modified.gitlab_token = true;
+            self.gitlab_token = std::mem::take(token);
+        }
+        if let Some(ref mut url) = from.gitlab_base_url {
+            from.modified.gitlab_base_url = true;
+            self.gitlab_base_url = std::mem::take(url);
+        }
+        if let Some(ref mut url) = from.endpoint_url {
+            from.modified.endpoint_url = true;
+            self.endpoint_url = std::mem::take(url);
+        }
+        if let Some(ref mut token) = from.endpoint_token {
+            from.modified.endpoint_token = true;
+            self.endpoint_token = std::mem::take(token);
+        }
+        if let Some(limit) = from.history_limit {
+            modified = true;
+            self.history_limit = Some(limit);
+        }
         from.modified.any() || modified
     }
 }
@@ -158,6 +784,23 @@ impl BadgePreferences {
             modified = true;
             self.count = count_type;
         }
+        if let Some(sort) = from.sort {
+            modified = true;
+            self.sort = sort;
+        }
+        if let Some(position) = from.total_position {
+            modified = true;
+            self.total_position = position;
+        }
+        if let Some(updated_badge) = from.updated_badge {
+            modified = true;
+            from.modified.updated_badge = true;
+            self.updated_badge = updated_badge;
+        }
+        if from.link || from.no_link {
+            modified = true;
+            self.link = from.link;
+        }
         if let Some(format) = from.format {
             modified = true;
             self.format = format;
@@ -170,17 +813,198 @@ impl BadgePreferences {
             modified = true;
             self.label_color = std::mem::take(color);
         }
+        if let Some(ref mut color) = from.label_color_light {
+            modified = true;
+            self.label_color_light = std::mem::take(color);
+            if !self.label_color_light.is_none() && !matches!(self.format, BadgeFormat::Markdown) {
+                warn!(
+                    "Light mode label color is only used by the markdown format, \
+                    use command 'set-arg --format markdown' to output light and dark badges"
+                );
+            }
+        }
         if let Some(ref mut color) = from.color {
             modified = true;
             self.color = std::mem::take(color);
         }
+        if let Some(ref mut logo) = from.logo {
+            modified = true;
+            self.logo = (!logo.eq_ignore_ascii_case("none")).then(|| std::mem::take(logo));
+        }
+        if let Some(ref mut color) = from.logo_color {
+            modified = true;
+            self.logo_color = std::mem::take(color);
+            if self.logo.is_none() && !self.logo_color.is_none() {
+                warn!(
+                    "Logo color will not be displayed until a logo is set, \
+                    use command 'set-arg --logo <SLUG>' to set a logo"
+                );
+            }
+        }
+        if let Some(width) = from.logo_width {
+            modified = true;
+            self.logo_width = (width != 0).then_some(width);
+            if self.logo.is_none() && self.logo_width.is_some() {
+                warn!(
+                    "Logo width will not be applied until a logo is set, \
+                    use command 'set-arg --logo <SLUG>' to set a logo"
+                );
+            }
+        }
+        if let Some(seconds) = from.cache_seconds {
+            modified = true;
+            self.cache_seconds = (seconds != 0).then_some(seconds);
+        }
+        if let Some(ref mut label) = from.total_label {
+            modified = true;
+            self.total_label = Some(std::mem::take(label));
+        }
+        if let Some(ref mut color) = from.total_color {
+            modified = true;
+            self.total_color = std::mem::take(color);
+        }
         modified
     }
 }
 
+/// Reads a secret from `path`, or from stdin without echo if `path` is '-'
+fn read_secret(path: &Path, name: &str) -> io::Result<String> {
+    let secret = if path == Path::new("-") {
+        rpassword::prompt_password(format!("{name}: "))?
+    } else {
+        std::fs::read_to_string(path)?
+    };
+    let secret = secret.trim();
+    if secret.is_empty() {
+        return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("{name} can not be empty"),
+        ));
+    }
+    Ok(secret.to_string())
+}
+
+impl SetArgs {
+    fn read_secret_files(&mut self) -> io::Result<()> {
+        if let Some(path) = self.git_file.take() {
+            self.git = Some(read_secret(&path, "Github token")?);
+        }
+        if let Some(path) = self.nexus_file.take() {
+            self.nexus = Some(read_secret(&path, "Nexus api key")?);
+        }
+        Ok(())
+    }
+
+    /// Encodes the image given to `--logo-file` as a base64 data uri, which shields.io accepts in
+    /// place of a simple-icons slug
+    fn read_logo_file(&mut self) -> io::Result<()> {
+        /// Data uris over this length make for badge urls that some sites refuse to render
+        const LONG_LOGO_LEN: usize = 8192;
+
+        let Some(path) = self.logo_file.take() else {
+            return Ok(());
+        };
+        let mime = match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("png") => "image/png",
+            Some("jpg" | "jpeg") => "image/jpeg",
+            Some("gif") => "image/gif",
+            Some("webp") => "image/webp",
+            Some("svg") => "image/svg+xml",
+            _ => {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "Unsupported logo file type: {}, expected a png, jpg, gif, webp, or svg",
+                        path.display()
+                    ),
+                ))
+            }
+        };
+        let logo = format!(
+            "data:{mime};base64,{}",
+            BASE64.encode(std::fs::read(&path)?)
+        );
+        if logo.len() > LONG_LOGO_LEN {
+            warn!(
+                "Embedded logo is {} bytes once encoded, consider a smaller image to keep badge \
+                urls short",
+                logo.len()
+            );
+        }
+        self.logo = Some(logo);
+        Ok(())
+    }
+
+    /// Splits a repository url or `owner/repo` pair given to `--repo` into its parts, then
+    /// ensures `owner` and `repo` are plain names
+    fn normalize_repo(&mut self) -> io::Result<()> {
+        if let Some((owner, repo)) = self.repo.as_deref().and_then(split_repo_path) {
+            if self.owner.as_ref().is_some_and(|curr| *curr != owner) {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("'--owner' does not match the owner found in '--repo': {owner}"),
+                ));
+            }
+            info!("Using owner: {owner}, and repo: {repo}");
+            self.owner = Some(owner);
+            self.repo = Some(repo);
+        }
+        for (flag, name) in [("owner", &self.owner), ("repo", &self.repo)] {
+            let Some(name) = name else { continue };
+            if name.contains(['/', ':']) || name.contains(char::is_whitespace) {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("'--{flag}' expects just the {flag} name, found: {name}"),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the owner and repo of a GitHub repository url, ssh remote, or `owner/repo` pair
+fn split_repo_path(s: &str) -> Option<(String, String)> {
+    let s = s.trim();
+    let path = if let Some((_, rest)) = s.split_once("://") {
+        rest.split_once('/').map_or("", |(_, path)| path)
+    } else if let Some(rest) = s.strip_prefix("git@") {
+        rest.split_once(':').map_or("", |(_, path)| path)
+    } else {
+        s
+    };
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let (owner, repo) = path.split_once('/')?;
+    (!owner.is_empty() && !repo.is_empty() && !repo.contains('/'))
+        .then(|| (owner.to_string(), repo.to_string()))
+}
+
 pub async fn update_args_local(new: &mut SetArgs) -> Result<(), Error> {
+    new.read_secret_files()?;
+    new.read_logo_file()?;
+    new.normalize_repo()?;
+
     let mut curr_keys = Input::from_file()?;
-    let mut curr_badge = read::<BadgePreferences>(&PATHS.preferences).unwrap_or_default();
+    let mut curr_badge = read_badge_prefs()?;
+
+    if let Some(ref id) = new.gist {
+        if !new.yes
+            && !curr_keys.gist_id.is_empty()
+            && curr_keys.gist_id != *id
+            && !confirm(&format!(
+                "Replace previously stored gist_id: {}?",
+                curr_keys.gist_id
+            ))?
+        {
+            info!("Stored gist_id was not changed");
+            new.gist = None;
+        }
+    }
 
     let keys_modified = curr_keys.update(new);
     let pref_modified = curr_badge.update(new);
@@ -192,19 +1016,25 @@ pub async fn update_args_local(new: &mut SetArgs) -> Result<(), Error> {
 
         if let Some(ref prev_id) = new.gist {
             if !prev_id.is_empty() {
-                // MARK: XXX
-                // Do we require confirmation for these kind of overwrites?
-                println!("WARN: Previously stored gist_id: {prev_id}, was replaced");
+                warn!("Previously stored gist_id: {prev_id}, was replaced");
             }
         }
 
-        println!("Key(s) updated locally");
+        info!("Key(s) updated locally");
     }
 
     if pref_modified {
-        write(curr_badge, &PATHS.preferences)?;
-        println!("Badge preference(s) updated")
+        write(&curr_badge, &PATHS.preferences)?;
+        info!("Badge preference(s) updated");
+        info!(
+            "{}",
+            curr_badge
+                .styled(color_enabled(&io::stderr()))
+                .to_string()
+                .trim_end()
+        );
     }
+    set_badge_prefs(curr_badge);
 
     return_res
 }
@@ -217,7 +1047,11 @@ pub async fn update_args_remote(new: SetArgs) -> Result<(), Error> {
 
     let vars = VARS.get().expect("set on startup");
 
-    let public_key_task = (new.modified.git_token || new.modified.nexus_key).then(get_public_key);
+    let public_key_task = (new.modified.git_token
+        || new.modified.nexus_key
+        || new.modified.gitlab_token
+        || new.modified.endpoint_token)
+        .then(get_public_key);
     let set_gist_id_task = new
         .modified
         .gist_id
@@ -243,100 +1077,624 @@ pub async fn update_args_remote(new: SetArgs) -> Result<(), Error> {
 
         propagate_err!(set_git_token_res);
         propagate_err!(set_nexus_key_res);
+
+        if new.modified.gitlab_token {
+            set_repository_secret(ENV_NAME_GITLAB, &vars.gitlab_token, &public_key).await?;
+        }
+        if new.modified.endpoint_token {
+            set_repository_secret(ENV_NAME_ENDPOINT_TOKEN, &vars.endpoint_token, &public_key)
+                .await?;
+        }
     }
 
     propagate_err!(set_gist_id_res);
 
+    if new.modified.gist_file {
+        set_repository_variable(ENV_NAME_GIST_FILE, gist_file_name()).await?;
+    }
+    if new.modified.backend {
+        set_repository_variable(ENV_NAME_BACKEND, vars.backend.as_str()).await?;
+    }
+    if new.modified.gitlab_base_url {
+        // Repository variables can not be empty, a cleared url is stored as the default
+        set_repository_variable(ENV_NAME_GITLAB_API_URL, vars.gitlab_base_url()).await?;
+    }
+    if new.modified.endpoint_url {
+        set_repository_variable(ENV_NAME_ENDPOINT_URL, &vars.endpoint_url).await?;
+    }
+    if let Some(updated_badge) = new.updated_badge.filter(|_| new.modified.updated_badge) {
+        set_repository_variable(ENV_NAME_UPDATED_BADGE, &updated_badge.to_string()).await?;
+    }
+
     Ok(())
 }
 
-pub async fn process(input_mods: Vec<Mod>, on_remote: bool) -> Result<(), Error> {
-    let (output_res, verify_res) =
-        tokio::join!(update_download_counts(input_mods, on_remote), verify_gist());
+/// With `dry_run` set the remote gist and local files are left untouched, what would have been
+/// written is printed instead. With `no_badges` set the badges file is not written. Returns if the
+/// remote gist was (or would have been) updated
+pub async fn process(
+    input_mods: Vec<Mod>,
+    on_remote: bool,
+    dry_run: bool,
+    no_badges: bool,
+    mode: OutputMode,
+) -> Result<bool, Error> {
+    let prev_output = (!on_remote && !mode.is_json()).then(|| read::<Output>(&PATHS.output).ok());
 
-    let (gist_endpoint, prev_remote) = verify_res?;
+    let (output_res, verify_res) = tokio::join!(
+        update_download_counts(input_mods, on_remote || dry_run),
+        verify_gist()
+    );
+
+    let prev_remote = verify_res?;
     let output = output_res?;
 
+    if let Some(prev_output) = prev_output {
+        print_deltas(prev_output.as_ref().map(|prev| &prev.mods), &output.mods);
+    }
+
     let new_content = serde_json::to_string_pretty(&output)?;
 
-    if prev_remote.content()? != new_content {
-        update_remote(&gist_endpoint, new_content).await?;
-    } else {
+    let prev_content = prev_remote.content()?;
+    let updated = !output.counts_match(prev_content)
+        || prev_remote.description_outdated()
+        || (updated_badge_enabled(on_remote) && output.updated_at_stale(prev_content));
+    let mod_ct = output.mods.len() - 1;
+
+    if dry_run {
+        if mode.is_json() {
+            println!(
+                "{}",
+                serde_json::json!({
+                    "status": "ok",
+                    "dry_run": true,
+                    "updated": updated,
+                    "mods": mod_ct,
+                    "output": output,
+                })
+            );
+            return Ok(updated);
+        }
         println!(
-            "Download counts for tracked mod(s) have not changed, remote gist was not modified"
+            "Dry run, remote gist {} be modified. Would be content:\n{new_content}\n",
+            if updated { "would" } else { "would not" }
         );
+        print_badges(output, prev_remote.universal_url()?)?;
+        return Ok(updated);
+    }
+
+    if updated {
+        VARS.get()
+            .expect("set on startup")
+            .backend
+            .update(new_content)
+            .await?;
+    } else if !mode.is_json() {
+        info!("Download counts for tracked mod(s) have not changed, remote gist was not modified");
+    }
+
+    if on_remote {
+        if let Err(err) = write_actions_summary(&output, updated, prev_remote.universal_url()?) {
+            warn!("{err}, GitHub actions summary was not written");
+        }
     }
 
     if !on_remote {
-        write_badges(output, prev_remote.universal_url()?)?;
+        if let Err(err) = append_history(&output.mods) {
+            warn!(
+                "{err}, download counts were not saved to: {}",
+                PATHS.history
+            );
+        }
+        let vars = VARS.get().expect("set on startup");
+        cache_universal_url(&vars.gist_id, prev_remote.universal_url()?);
+        if !no_badges {
+            write_badges(output, prev_remote.universal_url()?)?;
+        }
+    }
+
+    if mode.is_json() {
+        println!(
+            "{}",
+            serde_json::json!({ "status": "ok", "updated": updated, "mods": mod_ct })
+        );
+    }
+    Ok(updated)
+}
+
+/// Renders the badges file from the local output file, labels are taken from the registered mods
+/// as they are not stored in the output file. When the url badges read counts from is not cached
+/// it is fetched from the remote once and cached, unless `offline` is set
+pub async fn write_badges_only(input_mods: &[Mod], offline: bool) -> Result<(), Error> {
+    let mut output = match read::<Output>(&PATHS.output) {
+        Ok(output) => output,
+        Err(Error::Io(err)) if err.kind() == ErrorKind::NotFound => {
+            return Err(Error::NotSetup(
+                "No download counts saved locally, run nexus_badges without a command first",
+            ))
+        }
+        Err(err) => return Err(err),
+    };
+    let universal_url = match cached_universal_url() {
+        Some(url) => url,
+        None if offline => {
+            return Err(Error::NotSetup(
+                "The url badges read counts from is not cached, use command 'badges' or run \
+                without '--badges-only' once to cache it",
+            ))
+        }
+        None => {
+            let url = verify_gist().await?.universal_url()?.to_string();
+            cache_universal_url(&VARS.get().expect("set on startup").gist_id, &url);
+            url
+        }
+    };
+
+    for (key, entry) in output.mods.iter_mut() {
+        entry.label = input_mods
+            .iter()
+            .find(|details| match details.key {
+                Some(ref mod_key) => mod_key == key,
+                None => details.url() == entry.url,
+            })
+            .and_then(|details| details.label.clone());
+    }
+
+    write_badges(output, &universal_url)
+}
+
+/// Re-fetches the details of each tracked mod so a renamed mod gets its new name in the output and
+/// badge files, mods published since by registered authors are registered first. Unlike `process`
+/// no history snapshot is saved, and the remote is only updated with `push`
+pub async fn refresh(mut input_mods: Vec<Mod>, push: bool, mode: OutputMode) -> Result<(), Error> {
+    let vars = VARS.get().expect("set on startup");
+
+    let mut registered = 0;
+    for author in vars.authors.iter() {
+        match register_author_mods(&mut input_mods, author).await {
+            Ok((added, _)) => registered += added.len(),
+            Err(err) => warn!("Could not check for new mods by {}, {err}", author.name),
+        }
+    }
+    if registered != 0 {
+        input_mods.clone().write_and_try_set_remote().await?;
+        if !mode.is_json() {
+            info!("{registered} new mod(s) by registered authors registered");
+        }
+    }
+
+    let prev_names = read::<Output>(&PATHS.output)
+        .map(|prev| {
+            prev.mods
+                .into_iter()
+                .map(|(key, details)| (key, details.name))
+                .collect::<HashMap<_, _>>()
+        })
+        .unwrap_or_default();
+
+    let (output_res, verify_res) =
+        tokio::join!(update_download_counts(input_mods, false), verify_gist());
+
+    let prev_remote = verify_res?;
+    let output = output_res?;
+
+    let renamed = output
+        .mods
+        .iter()
+        .filter(|(key, _)| *key != TOTAL_KEY)
+        .filter_map(|(key, details)| {
+            let prev = prev_names.get(key)?;
+            (*prev != details.name).then(|| (key.clone(), prev.clone(), details.name.clone()))
+        })
+        .collect::<Vec<_>>();
+    let mod_ct = output.mods.len() - 1;
+
+    if push {
+        vars.backend
+            .update(serde_json::to_string_pretty(&output)?)
+            .await?;
+    }
+    cache_universal_url(&vars.gist_id, prev_remote.universal_url()?);
+    write_badges(output, prev_remote.universal_url()?)?;
+
+    if mode.is_json() {
+        println!(
+            "{}",
+            serde_json::json!({
+                "status": "ok",
+                "renamed": renamed.iter().map(|(key, from, to)| serde_json::json!({
+                    "key": key,
+                    "from": from,
+                    "to": to,
+                })).collect::<Vec<_>>(),
+                "registered": registered,
+                "pushed": push,
+                "mods": mod_ct,
+            })
+        );
+        return Ok(());
+    }
+
+    if renamed.is_empty() {
+        info!("No tracked mods have been renamed");
+    }
+    for (_, from, to) in renamed.iter() {
+        info!("Renamed: {from} -> {to}");
     }
     Ok(())
 }
 
+/// Prints a pass/fail line for each check, returns `false` if any check failed
+pub async fn validate(mode: OutputMode) -> bool {
+    let config = Config::from_startup();
+    let (nexus, gist, repo) = tokio::join!(
+        validate_key(&config),
+        async {
+            verify_remote_token()?;
+            verify_gist().await.map(drop)
+        },
+        async {
+            verify_git()?;
+            verify_actions_permissions().await
+        },
+    );
+
+    let checks = [
+        ("Nexus api key", nexus),
+        (remote_check_label(), gist),
+        ("Git token & repository", repo),
+    ];
+
+    if mode.is_json() {
+        let passed = checks
+            .iter()
+            .all(|(_, res)| matches!(res, Ok(()) | Err(Error::NotSetup(_))));
+        println!(
+            "{}",
+            serde_json::json!({
+                "status": if passed { "ok" } else { "failed" },
+                "checks": checks.iter().map(|(check, res)| match res {
+                    Ok(()) => serde_json::json!({ "check": check, "result": "pass" }),
+                    Err(Error::NotSetup(_)) => {
+                        serde_json::json!({ "check": check, "result": "skip" })
+                    }
+                    Err(err) => serde_json::json!({
+                        "check": check,
+                        "result": "fail",
+                        "error": err.to_string(),
+                    }),
+                }).collect::<Vec<_>>(),
+            })
+        );
+        return passed;
+    }
+
+    checks
+        .into_iter()
+        .fold(true, |passed, (check, res)| match res {
+            Ok(()) => {
+                println!("[PASS] {check}");
+                passed
+            }
+            Err(Error::NotSetup(_)) => {
+                println!("[SKIP] {check}, not setup");
+                passed
+            }
+            Err(err) => {
+                println!("[FAIL] {check}: {err}");
+                false
+            }
+        })
+}
+
 pub async fn init_remote(input_mods: Vec<Mod>) -> Result<(), Error> {
-    verify_git()?;
-    let mut input = Input::from(VARS.get().expect("set on startup"), input_mods.clone());
+    create_gist(input_mods).await?;
+    Ok(())
+}
+
+fn remote_check_label() -> &'static str {
+    match VARS.get().expect("set on startup").backend {
+        Backend::Github => "Git token & gist",
+        Backend::Gitlab => "GitLab token & snippet",
+        Backend::Http => "Endpoint token & url",
+    }
+}
+
+/// Returns the id of the newly created gist
+async fn create_gist(input_mods: Vec<Mod>) -> Result<String, Error> {
+    verify_remote_token()?;
+    let vars = VARS.get().expect("set on startup");
+    let mut input = Input::from(vars, input_mods.clone());
     let output = update_download_counts(input_mods, false).await?;
 
-    let mut meta = create_remote(serde_json::to_string_pretty(&output)?).await?;
+    let mut meta = vars
+        .backend
+        .create(serde_json::to_string_pretty(&output)?)
+        .await?;
+    cache_universal_url(&meta.id, meta.universal_url()?);
 
     let swapped_old = !input.gist_id.is_empty() && input.gist_id != meta.id;
 
-    println!("New gist_id: {}", meta.id);
+    info!("New gist_id: {}", meta.id);
 
     std::mem::swap(&mut input.gist_id, &mut meta.id);
+    let new_id = input.gist_id.clone();
     write(input, &PATHS.input)?;
 
     if swapped_old {
-        println!("Previous gist_id: {}, was replaced", meta.id);
+        warn!("Previous gist_id: {}, was replaced", meta.id);
     }
 
     write_badges(output, meta.universal_url()?)?;
 
+    Ok(new_id)
+}
+
+/// Prompts for credentials and stores them, the remote half of setup is ran by [`setup_remote`]  
+/// NOTE: this must run before `startup` so stored credentials are read into `VARS`
+pub async fn setup_credentials() -> Result<(), Error> {
+    println!(
+        "Nexus Badges setup\n\
+        Press enter to keep the currently stored value, or ctrl+c to cancel"
+    );
+
+    let prompts = tokio::task::spawn_blocking(|| -> io::Result<SetArgs> {
+        Ok(SetArgs {
+            nexus: prompt("Nexus personal api key", true)?,
+            git: prompt("GitHub fine-grained token", true)?,
+            owner: prompt(
+                "GitHub user name [Required for GitHub actions setup]",
+                false,
+            )?,
+            repo: prompt(
+                "Repository containing 'automation.yml' [Required for GitHub actions setup]",
+                false,
+            )?,
+            yes: true,
+            ..Default::default()
+        })
+    });
+
+    let mut args = tokio::select! {
+        res = prompts => res??,
+        _ = tokio::signal::ctrl_c() => {
+            println!("\nSetup cancelled, no changes were saved");
+            std::process::exit(130)
+        }
+    };
+
+    match update_args_local(&mut args).await {
+        Ok(()) | Err(Error::NotSetup(_)) => Ok(()),
+        Err(err @ Error::Missing(_)) => {
+            warn!("{err}");
+            Ok(())
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// When both the gist and GitHub actions are initialized, the repository secrets are set while
+/// the download counts for the new gist are being fetched, as neither depends on the other
+pub async fn setup_remote(input_mods: Vec<Mod>) -> Result<(), Error> {
+    let vars = VARS.get().expect("set on startup");
+
+    let init_gist = confirm("Initialize a new remote gist?")?;
+    let init_actions = verify_repo().is_ok() && confirm("Initialize GitHub actions automation?")?;
+    if init_actions {
+        verify_actions_permissions().await?;
+    }
+
+    // Looking up the repository public key is read only, so it can overlap the count fetch of the
+    // new gist. Secrets are only written once the gist was created
+    let (gist_res, public_key_res) = conditional_join(
+        init_gist.then(|| create_gist(input_mods.clone())),
+        init_actions.then(get_public_key),
+    )
+    .await;
+
+    let gist_id = match gist_res.transpose()? {
+        Some(new_id) => Cow::Owned(new_id),
+        None => Cow::Borrowed(vars.gist_id.as_str()),
+    };
+
+    if let Some(public_key_res) = public_key_res {
+        set_repository_secrets(&public_key_res?).await?;
+        set_repository_variables(input_mods, &gist_id).await?;
+        set_workflow_state(Workflow::Enable).await?;
+    }
+
+    info!("Setup complete!");
+    Ok(())
+}
+
+pub async fn delete_gist(input_mods: Vec<Mod>, skip_confirm: bool) -> Result<(), Error> {
+    verify_remote_token()?;
+    verify_gist().await?;
+    let vars = VARS.get().expect("set on startup");
+
+    if !skip_confirm
+        && !confirm(&format!(
+            "Permanently delete remote gist: {}?",
+            vars.gist_id
+        ))?
+    {
+        info!("Remote gist was not deleted");
+        return Ok(());
+    }
+
+    vars.backend.delete().await?;
+
+    let mut input = Input::from(vars, input_mods);
+    input.gist_id.clear();
+    write(input, &PATHS.input)?;
+
+    info!("Stored gist_id cleared");
     Ok(())
 }
 
 pub async fn init_actions(input_mods: Vec<Mod>) -> Result<(), Error> {
-    update_remote_variables(input_mods).await?;
+    let gist_id = &VARS.get().expect("set on startup").gist_id;
+    verify_actions_permissions().await?;
+    update_remote_variables(input_mods, gist_id).await?;
     set_workflow_state(Workflow::Enable).await?;
     Ok(())
 }
 
-async fn update_remote_variables(input_mods: Vec<Mod>) -> Result<(), Error> {
+/// Prints if the automation workflow is enabled, along with the conclusion and start time of its
+/// latest run
+pub async fn print_workflow_status(mode: OutputMode) -> Result<(), Error> {
+    let (details, latest_run) = get_workflow_status().await?;
+    let enabled = details.state == "active";
+
+    if mode.is_json() {
+        println!(
+            "{}",
+            serde_json::json!({
+                "status": "ok",
+                "enabled": enabled,
+                "state": details.state,
+                "url": details.html_url,
+                "latest_run": latest_run.map(|run| serde_json::json!({
+                    "status": run.status,
+                    "conclusion": run.conclusion,
+                    "event": run.event,
+                    "started_at": run.run_started_at,
+                    "url": run.html_url,
+                })),
+            })
+        );
+        return Ok(());
+    }
+
+    println!(
+        "Automation workflow: {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+    if details.state == "disabled_inactivity" {
+        println!("GitHub disabled the workflow due to repository inactivity, use command 'automation enable' to resume it");
+    }
+    match latest_run {
+        Some(run) => {
+            // GitHub timestamps are ISO 8601, e.g. "2024-01-31T12:00:00Z"
+            let started = match run.run_started_at.get(..16) {
+                Some(date_time) => Cow::Owned(format!("{} UTC", date_time.replacen('T', " ", 1))),
+                None => Cow::Borrowed(run.run_started_at.as_str()),
+            };
+            let outcome = run.conclusion.as_deref().unwrap_or(&run.status);
+            println!("Latest run: {outcome}, started {started} ({})", run.event);
+            println!("{}", run.html_url);
+        }
+        None => println!("Latest run: workflow has not run yet"),
+    }
+    Ok(())
+}
+
+async fn update_remote_variables(input_mods: Vec<Mod>, gist_id: &str) -> Result<(), Error> {
     verify_repo()?;
 
-    let vars = VARS.get().expect("set on startup");
+    let (secrets_res, variables_res) = tokio::join!(
+        async { set_repository_secrets(&get_public_key().await?).await },
+        set_repository_variables(input_mods, gist_id)
+    );
+
+    variables_res?;
+    secrets_res
+}
+
+async fn set_repository_variables(input_mods: Vec<Mod>, gist_id: &str) -> Result<(), Error> {
     let mods_str =
         serde_json::to_string(&input_mods).expect("`Vec<Mod>` is always ok to stringify");
-    let (public_key_res, gist_id_res, input_mods_res) = tokio::join!(
-        get_public_key(),
-        set_repository_variable(ENV_NAME_GIST_ID, &vars.gist_id),
-        set_repository_variable(ENV_NAME_MODS, &mods_str)
+    let vars = VARS.get().expect("set on startup");
+    let (gist_id_res, gist_file_res, input_mods_res, backend_res) = tokio::join!(
+        set_repository_variable(ENV_NAME_GIST_ID, gist_id),
+        set_repository_variable(ENV_NAME_GIST_FILE, gist_file_name()),
+        set_repository_variable(ENV_NAME_MODS, &mods_str),
+        set_repository_variable(ENV_NAME_BACKEND, vars.backend.as_str())
     );
 
     gist_id_res?;
+    gist_file_res?;
     input_mods_res?;
-    let public_key = public_key_res?;
+    backend_res?;
+
+    if !vars.gitlab_base_url.is_empty() {
+        set_repository_variable(ENV_NAME_GITLAB_API_URL, &vars.gitlab_base_url).await?;
+    }
+    if !vars.endpoint_url.is_empty() {
+        set_repository_variable(ENV_NAME_ENDPOINT_URL, &vars.endpoint_url).await?;
+    }
+    if badge_prefs()?.updated_badge {
+        set_repository_variable(ENV_NAME_UPDATED_BADGE, "true").await?;
+    }
+    Ok(())
+}
+
+async fn set_repository_secrets(public_key: &RepositoryPublicKey) -> Result<(), Error> {
+    let vars = VARS.get().expect("set on startup");
 
     let (git_secret_res, nexus_secret_res) = tokio::join!(
-        set_repository_secret(ENV_NAME_GIT, &vars.git_token, &public_key),
-        set_repository_secret(ENV_NAME_NEXUS, &vars.nexus_key, &public_key)
+        set_repository_secret(ENV_NAME_GIT, &vars.git_token, public_key),
+        set_repository_secret(ENV_NAME_NEXUS, &vars.nexus_key, public_key)
     );
 
     git_secret_res?;
     nexus_secret_res?;
 
+    match vars.backend {
+        Backend::Github => Ok(()),
+        Backend::Gitlab => {
+            set_repository_secret(ENV_NAME_GITLAB, &vars.gitlab_token, public_key).await
+        }
+        Backend::Http => {
+            set_repository_secret(ENV_NAME_ENDPOINT_TOKEN, &vars.endpoint_token, public_key).await
+        }
+    }
+}
+
+/// NOTE: this command is not supported on local  
+/// Variables are set one at a time, stopping at the first that fails
+pub async fn set_repo_vars(
+    vars: Vec<(String, String)>,
+    delete_cache: Option<&str>,
+) -> Result<(), Error> {
+    VARS.set(StartupVars::git_api_only()?)
+        .expect("`startup` never gets to run");
+
+    let set_task = async {
+        for (name, value) in vars.iter() {
+            set_repository_variable(name, value).await?;
+            info!("Repository variable: {name}, set");
+        }
+        Ok::<_, Error>(())
+    };
+
+    let (delete_res, set_res) =
+        conditional_join(delete_cache.map(delete_cache_by_key), Some(set_task)).await;
+
+    propagate_err!(set_res);
+    propagate_err!(delete_res);
+
     Ok(())
 }
 
 /// NOTE: this command is not supported on local
-pub async fn update_cache_key(old: Option<&str>, new: &str) -> Result<(), Error> {
+pub async fn update_cache_key(
+    old: Option<&str>,
+    prefix: Option<&str>,
+    new: &str,
+) -> Result<(), Error> {
     const CACHE_KEY: &str = "CACHED_BIN";
 
     VARS.set(StartupVars::git_api_only()?)
         .expect("`startup` never gets to run");
 
-    let delete_task = old.map(delete_cache_by_key);
+    let delete_task = (old.is_some() || prefix.is_some()).then_some(async move {
+        match (old, prefix) {
+            (Some(old), _) => delete_cache_by_key(old).await,
+            (None, Some(prefix)) => delete_caches_by_prefix(prefix, new).await.map(drop),
+            (None, None) => unreachable!("by `then_some` condition"),
+        }
+    });
 
     let (delete_res, set_res) =
         conditional_join(delete_task, Some(set_repository_variable(CACHE_KEY, new))).await;
@@ -346,3 +1704,44 @@ pub async fn update_cache_key(old: Option<&str>, new: &str) -> Result<(), Error>
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn add_mod_rejects_domain_case_duplicate() {
+        crate::tests::temp_config_dir();
+        let tracked = vec![Mod::from_str("SkyrimSpecialEdition:1").unwrap()];
+        let duplicate = Mod::from_str("skyrimspecialedition:1").unwrap();
+
+        let err = tracked
+            .add_mod(duplicate, OutputMode::Human)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::Io(ref err) if err.kind() == ErrorKind::InvalidInput),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn malformed_preferences_are_not_overwritten() {
+        crate::tests::temp_config_dir();
+        let truncated = br#"{ "label": "My Mods", "sort": "#;
+        std::fs::write(PATHS.preferences.as_ref(), truncated).unwrap();
+
+        assert!(read_badge_prefs().is_err());
+
+        let mut args = SetArgs {
+            label: Some(String::from("New label")),
+            ..Default::default()
+        };
+        assert!(update_args_local(&mut args).await.is_err());
+        assert_eq!(
+            std::fs::read(PATHS.preferences.as_ref()).unwrap(),
+            truncated
+        );
+    }
+}
@@ -7,55 +7,130 @@ pub mod models {
 }
 pub mod services {
     pub mod git;
+    pub mod gitlab;
+    pub mod http;
     pub mod nexus;
+    pub mod remote;
 }
 
 use crate::{
     models::{
-        badge_options::{BadgePreferences, EncodedFields},
-        cli::{Commands, Mod},
+        badge_options::{BadgeFormat, BadgePreferences, EncodedFields, TotalPosition},
+        cli::{Backend, ColorChoice, Commands, Mod, OutputMode},
         error::Error,
-        json_data::{GistResponse, Input, ModDetails, Version},
+        json_data::{
+            Author, GistResponse, Input, ModDetails, Output, RemoteCache, Snapshot, SnapshotCounts,
+            Version, VersionCheck,
+        },
     },
-    services::git::{get_remote, gist_id_endpoint},
+    services::{git::is_gist_id, remote::RemoteStore},
 };
+use clap::ValueEnum;
 use constcat::concat;
+use log::{debug, info, warn, LevelFilter};
 use percent_encoding::{AsciiSet, CONTROLS};
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
 use serde::{Deserialize, Serialize};
 use std::{
     borrow::Cow,
-    collections::BTreeMap,
+    collections::{BTreeMap, VecDeque},
     fmt::Display,
     fs::File,
-    io::{self, BufRead, BufReader, BufWriter, ErrorKind, Write},
-    sync::{LazyLock, OnceLock},
+    io::{self, BufRead, BufReader, BufWriter, ErrorKind, IsTerminal, Write},
+    path::{Path, PathBuf},
+    sync::{LazyLock, Mutex, OnceLock},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
+use tokio::task::JoinHandle;
 
 const DEFAULT_IO_DIR_NAME: &str = "io";
 const INPUT_FILE_NAME: &str = "input.json";
 const OUTPUT_FILE_NAME: &str = "output.json";
 const PREFERENCES_FILE_NAME: &str = "badge_preferences.json";
+const HISTORY_FILE_NAME: &str = "history.json";
+const VERSION_CHECK_FILE_NAME: &str = "version_check.json";
+const REMOTE_CACHE_FILE_NAME: &str = "remote_cache.json";
 const BADGES_FILE_NAME: &str = "badges.md";
 
-pub static PATHS: LazyLock<FilePaths> = LazyLock::new(init_paths);
+pub static PATHS: LazyLock<FilePaths> = LazyLock::new(|| {
+    let paths = init_paths();
+    match PATH_OVERRIDES.get() {
+        Some(overrides) => paths.with_overrides(overrides),
+        None => paths,
+    }
+});
+static PATH_OVERRIDES: OnceLock<PathOverrides> = OnceLock::new();
 
 const BADGE_URL: &str = "https://shields.io/badges/dynamic-json-badge";
 
 const ENV_NAME_NEXUS: &str = "NEXUS_KEY";
 const ENV_NAME_GIT: &str = "GIT_TOKEN";
 const ENV_NAME_GIST_ID: &str = "GIST_ID";
+const ENV_NAME_GIST_FILE: &str = "GIST_FILE";
 const ENV_NAME_MODS: &str = "TRACKED_MODS";
+/// Path to a json file of tracked mods, read when `ENV_NAME_MODS` is unset or empty. Lists too large
+/// for a repository variable can be checked into the repository instead
+const ENV_NAME_MODS_FILE: &str = "TRACKED_MODS_FILE";
+const ENV_NAME_GIT_API_URL: &str = "GITHUB_API_URL";
+const ENV_NAME_BACKEND: &str = "REMOTE_BACKEND";
+const ENV_NAME_GITLAB: &str = "GITLAB_TOKEN";
+const ENV_NAME_GITLAB_API_URL: &str = "GITLAB_API_URL";
+const ENV_NAME_ENDPOINT_URL: &str = "ENDPOINT_URL";
+const ENV_NAME_ENDPOINT_TOKEN: &str = "ENDPOINT_TOKEN";
+/// Mirrors the `updated_badge` preference, remote runs have no preferences file to read it from
+const ENV_NAME_UPDATED_BADGE: &str = "UPDATED_BADGE";
 
 pub const OK_RESPONSE: u16 = 200;
 pub const CREATED_RESPONSE: u16 = 201;
 pub const UPDATED_RESPONSE: u16 = 204;
+pub const NOT_MODIFIED_RESPONSE: u16 = 304;
+pub const FORBIDDEN_RESPONSE: u16 = 403;
+pub const NOT_FOUND_RESPONSE: u16 = 404;
+pub const TOO_MANY_REQUESTS_RESPONSE: u16 = 429;
+
+const REPO_URL: &str = "https://github.com/WardLordRuby/nexus_badges";
+/// Replaces the repository url in the User-Agent with contact details of the person running this
+/// instance, as the Nexus api guidelines ask for a way to reach the operator of an application
+const ENV_NAME_CONTACT: &str = "NEXUS_BADGES_CONTACT";
 
 const VERSION_URL: &str =
     "https://gist.githubusercontent.com/WardLordRuby/b7ae290f2a7f1a20e9795170965c4a46/raw";
+/// Minimum time between background version checks
+const VERSION_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60 * 24);
+/// Background version checks that take longer than this are abandoned
+const VERSION_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
 
 pub const TOTAL_KEY: &str = "Totals";
+/// Bump when the layout of [`Output`] changes in a way existing badge queries can not read
+const OUTPUT_SCHEMA: u32 = 1;
+const UPDATED_KEY: &str = "updated_at";
+
+const DEFAULT_HISTORY_LIMIT: usize = 365;
 
 static VARS: OnceLock<StartupVars> = OnceLock::new();
+static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+/// `nexus_badges/<version> (+<contact>)`, sent with every request
+static USER_AGENT: LazyLock<String> = LazyLock::new(|| {
+    let contact = std::env::var(ENV_NAME_CONTACT)
+        .ok()
+        .map(|contact| contact.trim().to_string())
+        .filter(|contact| {
+            !contact.is_empty() && reqwest::header::HeaderValue::from_str(contact).is_ok()
+        });
+    format!(
+        "{}/{} (+{})",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        contact.as_deref().unwrap_or(REPO_URL)
+    )
+});
+static CLIENT_OPTIONS: OnceLock<ClientOptions> = OnceLock::new();
+static BADGE_PREFS: OnceLock<BadgePreferences> = OnceLock::new();
+static COLOR: OnceLock<ColorChoice> = OnceLock::new();
+static OUTPUT_MODE: OnceLock<OutputMode> = OnceLock::new();
+static VERSION_CHECK: Mutex<Option<JoinHandle<VersionCheckResult>>> = Mutex::new(None);
+
+type VersionCheckResult = Result<reqwest::Result<Option<String>>, tokio::time::error::Elapsed>;
 
 #[macro_export]
 macro_rules! unsupported {
@@ -68,7 +143,7 @@ macro_rules! unsupported {
 
     ($command:ident, on_local, $on_remote:expr) => {
         if !$on_remote {
-            eprintln!("'{}' is only supported on remote", $command);
+            nexus_badges::report_err(&format!("'{}' is only supported on remote", $command));
             return;
         }
     };
@@ -85,7 +160,7 @@ pub fn exit_on_remote(on_remote: bool, code: i32) {
 macro_rules! return_after {
     ($result:expr, $on_remote:expr) => {
         $result.unwrap_or_else(|err| {
-            eprintln!("{err}");
+            nexus_badges::report_err(&err);
             nexus_badges::exit_on_remote($on_remote, 1)
         });
         return;
@@ -95,15 +170,227 @@ macro_rules! return_after {
 #[macro_export]
 macro_rules! print_err {
     ($result:expr) => {
-        $result.unwrap_or_else(|err| eprintln!("{err}"))
+        $result.unwrap_or_else(|err| nexus_badges::report_err(&err))
     };
 }
 
+/// Scrubs the stored tokens and nexus key from `msg`, so they can not leak into logs or the public
+/// GitHub actions console through an error path
+pub(crate) fn redact(msg: &str) -> Cow<'_, str> {
+    const MASK: &str = "***";
+
+    let Some(vars) = VARS.get() else {
+        return Cow::Borrowed(msg);
+    };
+
+    [
+        vars.git_token.as_str(),
+        vars.gitlab_token.as_str(),
+        vars.endpoint_token.as_str(),
+        vars.nexus_key.as_str(),
+    ]
+    .into_iter()
+    .filter(|secret| !secret.is_empty())
+    .fold(Cow::Borrowed(msg), |msg, secret| {
+        if msg.contains(secret) {
+            Cow::Owned(msg.replace(secret, MASK))
+        } else {
+            msg
+        }
+    })
+}
+
+/// Routes log output to stderr, info level messages are printed without a prefix as they are
+/// regular status updates
+pub fn init_logger(level: LevelFilter) {
+    env_logger::Builder::new()
+        .filter_level(if level >= LevelFilter::Trace {
+            LevelFilter::Debug
+        } else {
+            LevelFilter::Warn
+        })
+        .filter_module(env!("CARGO_CRATE_NAME"), level)
+        .format(|buf, record| {
+            let args = record.args().to_string();
+            let msg = redact(&args);
+            match record.level() {
+                log::Level::Info => writeln!(buf, "{msg}"),
+                log::Level::Warn | log::Level::Error => {
+                    writeln!(buf, "{}: {msg}", record.level())
+                }
+                log::Level::Debug | log::Level::Trace => {
+                    writeln!(buf, "[{} {}] {msg}", record.level(), record.target())
+                }
+            }
+        })
+        .init();
+}
+
+/// User supplied network settings applied to every http client
+#[derive(Debug)]
+pub struct ClientOptions {
+    pub timeout: Duration,
+    /// Used in place of the proxy read from the `HTTPS_PROXY`, `HTTP_PROXY`, and `ALL_PROXY`
+    /// environment variables
+    pub proxy: Option<reqwest::Proxy>,
+    /// Trusted in addition to the system root certificates
+    pub root_certificates: Vec<reqwest::Certificate>,
+    /// Disables certificate verification, only meant for debugging
+    pub accept_invalid_certs: bool,
+}
+
+/// Reads every certificate in the PEM or DER encoded file at `path`
+pub fn read_certificates(path: &Path) -> Result<Vec<reqwest::Certificate>, Error> {
+    let contents = std::fs::read(path)
+        .map_err(|err| io::Error::new(err.kind(), format!("{}: {err}", path.display())))?;
+    let certificates = if contents.trim_ascii_start().starts_with(b"-----BEGIN") {
+        reqwest::Certificate::from_pem_bundle(&contents).ok()
+    } else {
+        reqwest::Certificate::from_der(&contents)
+            .ok()
+            .map(|cert| vec![cert])
+    };
+    certificates
+        .filter(|certs| !certs.is_empty())
+        .ok_or_else(|| {
+            Error::Io(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("{} is not a PEM or DER encoded certificate", path.display()),
+            ))
+        })
+}
+
+/// Builds the http client shared by all api calls, must be called before any network request
+pub fn init_client(options: ClientOptions) -> reqwest::Result<()> {
+    if options.accept_invalid_certs {
+        warn!(
+            "TLS certificate verification is disabled, responses can be read or altered by anyone \
+            on the network. Only use '--danger-insecure' for debugging"
+        );
+    }
+    CLIENT_OPTIONS.set(options).expect("only set");
+    CLIENT.set(client_builder().build()?).expect("only set");
+    Ok(())
+}
+
+/// Configuration every http client is built with
+pub(crate) fn client_builder() -> reqwest::ClientBuilder {
+    let options = CLIENT_OPTIONS.get().expect("set on startup");
+    let builder = options.root_certificates.iter().fold(
+        reqwest::Client::builder()
+            .timeout(options.timeout)
+            .user_agent(USER_AGENT.as_str())
+            .danger_accept_invalid_certs(options.accept_invalid_certs),
+        |builder, cert| builder.add_root_certificate(cert.clone()),
+    );
+    match options.proxy {
+        Some(ref proxy) => builder.proxy(proxy.clone()),
+        None => builder,
+    }
+}
+
+#[inline]
+pub(crate) fn client() -> &'static reqwest::Client {
+    CLIENT.get().expect("set on startup")
+}
+
 pub struct FilePaths {
     pub input: Cow<'static, str>,
     pub output: Cow<'static, str>,
     pub badges: Cow<'static, str>,
     pub preferences: Cow<'static, str>,
+    pub history: Cow<'static, str>,
+    pub version_check: Cow<'static, str>,
+    pub remote_cache: Cow<'static, str>,
+}
+
+/// User supplied locations that take priority over the platform default [`FilePaths`]
+#[derive(Debug, Default)]
+pub struct PathOverrides {
+    /// Directory to place every file in
+    pub dir: Option<PathBuf>,
+    pub input: Option<PathBuf>,
+    pub output: Option<PathBuf>,
+    pub badges: Option<PathBuf>,
+}
+
+/// Sets how errors given to [`report_err`] are written, defaults to [`OutputMode::Human`] if never
+/// called
+pub fn set_output_mode(mode: OutputMode) {
+    OUTPUT_MODE.set(mode).expect("only set");
+}
+
+/// Writes `err` to stderr. In JSON output mode `err` is written to stdout as
+/// `{"status": "error", "message": ...}` and the process exits with code 1, so scripts can tell a
+/// failure apart from an empty result
+pub fn report_err(err: &impl Display) {
+    if !OUTPUT_MODE.get().is_some_and(|mode| mode.is_json()) {
+        eprintln!("{err}");
+        return;
+    }
+    let message = err.to_string();
+    println!(
+        "{}",
+        serde_json::json!({
+            "status": "error",
+            "message": redact(&message),
+        })
+    );
+    std::process::exit(1)
+}
+
+/// Sets when output is colored, defaults to [`ColorChoice::Auto`] if never called
+pub fn set_color_choice(choice: ColorChoice) {
+    COLOR.set(choice).expect("only set");
+}
+
+/// Must be checked before writing any ANSI styled output to `stream`
+pub(crate) fn color_enabled(stream: &impl IsTerminal) -> bool {
+    match COLOR.get().copied().unwrap_or_default() {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            std::env::var_os("NO_COLOR").is_none_or(|val| val.is_empty()) && stream.is_terminal()
+        }
+    }
+}
+
+/// Must be called before `PATHS` is first accessed for the overrides to take effect
+pub fn set_path_overrides(overrides: PathOverrides) {
+    PATH_OVERRIDES.set(overrides).expect("only set");
+}
+
+impl FilePaths {
+    /// Single rolling backup of the previous contents of `input`
+    pub fn input_backup(&self) -> String {
+        format!("{}.bak", self.input)
+    }
+
+    fn with_overrides(mut self, overrides: &PathOverrides) -> Self {
+        fn owned(path: &Path) -> Cow<'static, str> {
+            Cow::Owned(path.to_string_lossy().into_owned())
+        }
+
+        if let Some(ref dir) = overrides.dir {
+            self.input = owned(&dir.join(INPUT_FILE_NAME));
+            self.output = owned(&dir.join(OUTPUT_FILE_NAME));
+            self.badges = owned(&dir.join(BADGES_FILE_NAME));
+            self.preferences = owned(&dir.join(PREFERENCES_FILE_NAME));
+            self.history = owned(&dir.join(HISTORY_FILE_NAME));
+            self.version_check = owned(&dir.join(VERSION_CHECK_FILE_NAME));
+            self.remote_cache = owned(&dir.join(REMOTE_CACHE_FILE_NAME));
+        }
+        if let Some(ref input) = overrides.input {
+            self.input = owned(input);
+        }
+        if let Some(ref output) = overrides.output {
+            self.output = owned(output);
+        }
+        if let Some(ref badges) = overrides.badges {
+            self.badges = owned(badges);
+        }
+        self
+    }
 }
 
 #[cfg(any(target_os = "linux", target_os = "macos"))]
@@ -113,7 +400,7 @@ fn init_paths() -> FilePaths {
     let mut exe_dir = match std::env::current_exe() {
         Ok(dir) => dir,
         Err(err) => {
-            eprintln!(
+            warn!(
                 "Could not locate executable, {err}\n\
                 Using executable local paths for input + output"
             );
@@ -129,12 +416,24 @@ fn init_paths() -> FilePaths {
 
     let home = std::env::var("HOME").expect("valid var on unix");
 
+    #[cfg(target_os = "linux")]
+    let app_dir = env!("CARGO_PKG_NAME").replace('_', "-");
+
     #[cfg(target_os = "linux")]
     let base = format!(
-        "{home}/.config/{}",
-        env!("CARGO_PKG_NAME").replace('_', "-")
+        "{}/{app_dir}",
+        xdg_dir("XDG_CONFIG_HOME").unwrap_or_else(|| format!("{home}/.config"))
+    );
+
+    #[cfg(target_os = "linux")]
+    let badges_dir = xdg_dir("XDG_DATA_HOME").map_or_else(
+        || format!("{home}/Documents"),
+        |data| format!("{data}/{app_dir}"),
     );
 
+    #[cfg(target_os = "macos")]
+    let badges_dir = format!("{home}/Documents");
+
     #[cfg(target_os = "macos")]
     let base = format!(
         "{home}/Library/{}",
@@ -144,14 +443,63 @@ fn init_paths() -> FilePaths {
     FilePaths {
         input: Cow::Owned(format!("{base}/{INPUT_FILE_NAME}")),
         output: Cow::Owned(format!("{base}/{OUTPUT_FILE_NAME}")),
-        badges: Cow::Owned(format!("{home}/Documents/{BADGES_FILE_NAME}")),
+        badges: Cow::Owned(format!("{badges_dir}/{BADGES_FILE_NAME}")),
         preferences: Cow::Owned(format!("{base}/{PREFERENCES_FILE_NAME}")),
+        history: Cow::Owned(format!("{base}/{HISTORY_FILE_NAME}")),
+        version_check: Cow::Owned(format!("{base}/{VERSION_CHECK_FILE_NAME}")),
+        remote_cache: Cow::Owned(format!("{base}/{REMOTE_CACHE_FILE_NAME}")),
     }
 }
 
+/// Per the XDG Base Directory spec, unset, empty, and relative values are ignored
+#[cfg(target_os = "linux")]
+fn xdg_dir(var: &str) -> Option<String> {
+    std::env::var(var)
+        .ok()
+        .filter(|dir| dir.starts_with('/'))
+        .map(|dir| dir.trim_end_matches('/').to_string())
+}
+
 #[cfg(target_os = "windows")]
-const fn init_paths() -> FilePaths {
-    FilePaths::default()
+fn init_paths() -> FilePaths {
+    const PROGRAM_FILES_VARS: [&str; 2] = ["ProgramFiles", "ProgramFiles(x86)"];
+
+    let exe_dir = match std::env::current_exe() {
+        Ok(dir) => dir,
+        Err(err) => {
+            warn!(
+                "Could not locate executable, {err}\n\
+                Using executable local paths for input + output"
+            );
+            return FilePaths::default();
+        }
+    };
+
+    let installed = PROGRAM_FILES_VARS
+        .iter()
+        .filter_map(std::env::var_os)
+        .any(|program_files| exe_dir.starts_with(program_files));
+
+    if !installed {
+        return FilePaths::default();
+    }
+
+    let (Ok(app_data), Ok(user_profile)) = (std::env::var("APPDATA"), std::env::var("USERPROFILE"))
+    else {
+        return FilePaths::default();
+    };
+
+    let base = format!("{app_data}\\{}", env!("CARGO_PKG_NAME").replace('_', "-"));
+
+    FilePaths {
+        input: Cow::Owned(format!("{base}\\{INPUT_FILE_NAME}")),
+        output: Cow::Owned(format!("{base}\\{OUTPUT_FILE_NAME}")),
+        badges: Cow::Owned(format!("{user_profile}\\Documents\\{BADGES_FILE_NAME}")),
+        preferences: Cow::Owned(format!("{base}\\{PREFERENCES_FILE_NAME}")),
+        history: Cow::Owned(format!("{base}\\{HISTORY_FILE_NAME}")),
+        version_check: Cow::Owned(format!("{base}\\{VERSION_CHECK_FILE_NAME}")),
+        remote_cache: Cow::Owned(format!("{base}\\{REMOTE_CACHE_FILE_NAME}")),
+    }
 }
 
 impl FilePaths {
@@ -161,6 +509,13 @@ impl FilePaths {
             output: Cow::Borrowed(concat!(DEFAULT_IO_DIR_NAME, "/", OUTPUT_FILE_NAME)),
             badges: Cow::Borrowed(concat!(DEFAULT_IO_DIR_NAME, "/", BADGES_FILE_NAME)),
             preferences: Cow::Borrowed(concat!(DEFAULT_IO_DIR_NAME, "/", PREFERENCES_FILE_NAME)),
+            history: Cow::Borrowed(concat!(DEFAULT_IO_DIR_NAME, "/", HISTORY_FILE_NAME)),
+            version_check: Cow::Borrowed(concat!(
+                DEFAULT_IO_DIR_NAME,
+                "/",
+                VERSION_CHECK_FILE_NAME
+            )),
+            remote_cache: Cow::Borrowed(concat!(DEFAULT_IO_DIR_NAME, "/", REMOTE_CACHE_FILE_NAME)),
         }
     }
 }
@@ -203,13 +558,38 @@ impl Display for Commands {
             "{}",
             match self {
                 Commands::Add(_) => "add",
+                Commands::AddAuthor {
+                    domain: _,
+                    author: _,
+                } => "add-author",
                 Commands::Remove(_) => "remove",
+                Commands::List => "list",
                 Commands::SetArg(_) => "set-arg",
                 Commands::Automation { state: _ } => "automation",
+                Commands::Export { out: _ } => "export",
+                Commands::Dump { out: _, format: _ } => "dump",
+                Commands::Stats { since: _ } => "stats",
+                Commands::Refresh { push: _ } => "refresh",
+                Commands::Import { path: _, merge: _ } => "import",
+                Commands::Restore => "restore",
+                Commands::Badges => "badges",
+                Commands::Setup => "setup",
+                Commands::Validate => "validate",
                 Commands::Init => "init",
+                Commands::DeleteGist { yes: _ } => "delete-gist",
                 Commands::InitActions => "init-actions",
+                Commands::Completions { shell: _ } => "completions",
+                Commands::Manpage { out: _ } => "manpage",
                 Commands::Version => "version",
-                Commands::UpdateCacheKey { old: _, new: _ } => "repo-variable",
+                Commands::UpdateCacheKey {
+                    old: _,
+                    prefix: _,
+                    new: _,
+                } => "repo-variable",
+                Commands::SetRepoVar {
+                    vars: _,
+                    delete_cache: _,
+                } => "set-repo-var",
             }
         )
     }
@@ -226,14 +606,142 @@ impl ModDetails {
     fn add(&mut self, other: &Self) {
         self.mod_downloads += other.mod_downloads;
         self.mod_unique_downloads += other.mod_unique_downloads;
+        self.endorsements += other.endorsements;
     }
 
-    fn add_url(mut self, from: &Mod) -> Self {
+    fn set_downloads_combined(&mut self) {
+        self.downloads_combined = format!(
+            "{} / {} unique",
+            compact_count(self.mod_downloads),
+            compact_count(self.mod_unique_downloads)
+        );
+    }
+
+    fn add_details(mut self, from: &Mod) -> Self {
         self.url = from.url();
+        self.label = from.label.clone();
         self
     }
 }
 
+impl From<&BTreeMap<String, ModDetails>> for Snapshot {
+    fn from(value: &BTreeMap<String, ModDetails>) -> Self {
+        Snapshot {
+            timestamp: unix_timestamp(),
+            counts: value
+                .iter()
+                .map(|(uid, entry)| {
+                    let counts = SnapshotCounts {
+                        downloads: entry.mod_downloads,
+                        unique: entry.mod_unique_downloads,
+                    };
+                    (uid.clone(), counts)
+                })
+                .collect(),
+        }
+    }
+}
+
+const SECS_PER_DAY: u64 = 86_400;
+
+/// Formats seconds since the unix epoch as `YYYY-MM-DD HH:MM UTC`
+fn utc_date_time(timestamp: u64) -> String {
+    let (days, secs) = (timestamp / SECS_PER_DAY, timestamp % SECS_PER_DAY);
+
+    // Civil from days algorithm, see: https://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02} UTC",
+        secs / 3600,
+        secs % 3600 / 60
+    )
+}
+
+/// Parses a `YYYY-MM-DD` date into seconds since the unix epoch at the start of that day (UTC).
+/// Returns `None` for malformed or out of range dates, and dates before the unix epoch
+fn parse_utc_date(date: &str) -> Option<u64> {
+    let mut parts = date.splitn(3, '-');
+    let (year, month, day) = (
+        parts.next()?.parse::<u64>().ok()?,
+        parts.next()?.parse::<u64>().ok()?,
+        parts.next()?.parse::<u64>().ok()?,
+    );
+    if year < 1970 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // Days from civil algorithm, the inverse of the one used in `utc_date_time`
+    let y = year - u64::from(month <= 2);
+    let era = y / 400;
+    let yoe = y - era * 400;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let timestamp = (era * 146_097 + doe - 719_468) * SECS_PER_DAY;
+
+    // Days past the end of the month roll over into the next, which is caught by the round trip
+    utc_date_time(timestamp)
+        .starts_with(&format!("{year:04}-{month:02}-{day:02}"))
+        .then_some(timestamp)
+}
+
+impl Output {
+    fn new(mods: BTreeMap<String, ModDetails>) -> Self {
+        Output {
+            schema: OUTPUT_SCHEMA,
+            generated_by: concat!(env!("CARGO_PKG_NAME"), " v", env!("CARGO_PKG_VERSION"))
+                .to_string(),
+            updated_at: utc_date_time(unix_timestamp()),
+            mods,
+        }
+    }
+
+    /// Compares download counts only, as `updated_at` changes on every run
+    fn counts_match(&self, content: &str) -> bool {
+        let Ok(prev) = serde_json::from_str::<Output>(content) else {
+            return false;
+        };
+        let comparable = Output {
+            updated_at: prev.updated_at,
+            ..self.clone()
+        };
+        serde_json::to_string_pretty(&comparable).is_ok_and(|comparable| comparable == content)
+    }
+
+    /// `true` when the previous `updated_at` is from an earlier UTC day, so the "Last updated" badge
+    /// moves at most once a day while download counts stay the same
+    fn updated_at_stale(&self, content: &str) -> bool {
+        serde_json::from_str::<Output>(content).map_or(true, |prev| {
+            prev.updated_at.get(..10) != self.updated_at.get(..10)
+        })
+    }
+}
+
+/// Whether the "Last updated" badge is generated, read from the `UPDATED_BADGE` variable on remote
+fn updated_badge_enabled(on_remote: bool) -> bool {
+    if on_remote {
+        return std::env::var(ENV_NAME_UPDATED_BADGE)
+            .is_ok_and(|val| val.trim().eq_ignore_ascii_case("true"));
+    }
+    badge_prefs().is_ok_and(|prefs| prefs.updated_badge)
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock is set after the unix epoch")
+        .as_secs()
+}
+
 fn verify_added(mods: &[Mod]) -> Result<(), Error> {
     if mods.is_empty() {
         return Err(Error::Missing(
@@ -251,7 +759,7 @@ fn verify_nexus() -> Result<(), Error> {
         ));
     }
     if vars.git_token.is_empty() {
-        println!(
+        warn!(
             "Git fine-grained token missing, Use command 'set' to store private token\n\
             ouput will be saved locally"
         )
@@ -268,15 +776,62 @@ fn verify_git() -> Result<(), Error> {
     Ok(())
 }
 
-async fn verify_gist() -> Result<(String, GistResponse), Error> {
-    if VARS.get().expect("set on startup").gist_id.is_empty() {
+fn verify_gitlab() -> Result<(), Error> {
+    if VARS.get().expect("set on startup").gitlab_token.is_empty() {
+        return Err(Error::Missing(
+            "GitLab access token missing, Use command 'set --gitlab-token' to store private token",
+        ));
+    }
+    Ok(())
+}
+
+fn verify_endpoint() -> Result<(), Error> {
+    let vars = VARS.get().expect("set on startup");
+    if vars.endpoint_url.is_empty() {
+        return Err(Error::Missing(
+            "Endpoint url missing, Use command 'set --endpoint-url' to store the endpoint url",
+        ));
+    }
+    if vars.endpoint_token.is_empty() {
+        return Err(Error::Missing(
+            "Endpoint token missing, Use command 'set --endpoint-token' to store bearer token",
+        ));
+    }
+    Ok(())
+}
+
+/// Checks the token of the selected backend is stored
+fn verify_remote_token() -> Result<(), Error> {
+    match backend() {
+        Backend::Github => verify_git(),
+        Backend::Gitlab => verify_gitlab(),
+        Backend::Http => verify_endpoint(),
+    }
+}
+
+#[inline]
+fn backend() -> Backend {
+    VARS.get().expect("set on startup").backend
+}
+
+async fn verify_gist() -> Result<GistResponse, Error> {
+    let vars = VARS.get().expect("set on startup");
+    if vars.gist_id.is_empty() {
         return Err(Error::NotSetup(
             "Use command 'init' to initialize a new remote gist",
         ));
     }
-    let endpoint = gist_id_endpoint();
-    let meta = get_remote(&endpoint).await?;
-    Ok((endpoint, meta))
+    // The http backend stores its endpoint url in place of an id
+    if vars.backend != Backend::Http && !is_gist_id(&vars.gist_id) {
+        return Err(Error::Io(io::Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Stored gist_id: '{}', is not a valid id. Use command 'set-arg --gist' to replace it",
+                vars.gist_id
+            ),
+        )));
+    }
+    backend().get().await
 }
 
 fn verify_repo_from(owner: &str, repo: &str) -> Result<(), Error> {
@@ -306,12 +861,63 @@ fn verify_repo() -> Result<(), Error> {
     verify_repo_from(&vars.owner, &vars.repo)
 }
 
-async fn check_program_version() -> reqwest::Result<Option<String>> {
-    let version = reqwest::get(VERSION_URL).await?.json::<Version>().await?;
-    if version.latest != env!("CARGO_PKG_VERSION") {
-        return Ok(Some(version.message));
+impl VersionCheck {
+    fn update_message(&self) -> Option<String> {
+        (self.version.latest != env!("CARGO_PKG_VERSION")).then(|| self.version.message.clone())
     }
-    Ok(None)
+}
+
+/// Results are cached in 'version_check.json', unless `force` is set the check is skipped when
+/// the previous check is more recent than `VERSION_CHECK_INTERVAL`
+async fn check_program_version(force: bool) -> reqwest::Result<Option<String>> {
+    let prev = read::<VersionCheck>(&PATHS.version_check).ok();
+    let now = unix_timestamp();
+
+    if let Some(ref prev) = prev {
+        if !force && now.saturating_sub(prev.checked_at) < VERSION_CHECK_INTERVAL.as_secs() {
+            return Ok(prev.update_message());
+        }
+    }
+
+    let mut request = client().get(VERSION_URL);
+    if let Some(etag) = prev.as_ref().and_then(|prev| prev.etag.as_ref()) {
+        request = request.header(IF_NONE_MATCH, etag.as_str());
+    }
+    if let Some(modified) = prev.as_ref().and_then(|prev| prev.last_modified.as_ref()) {
+        request = request.header(IF_MODIFIED_SINCE, modified.as_str());
+    }
+    let response = request.send().await?;
+
+    let check = match prev {
+        Some(prev) if response.status() == NOT_MODIFIED_RESPONSE => {
+            debug!("Program version unchanged since last check");
+            VersionCheck {
+                checked_at: now,
+                ..prev
+            }
+        }
+        _ => {
+            let header = |name| {
+                response
+                    .headers()
+                    .get(name)
+                    .and_then(|value| value.to_str().ok())
+                    .map(String::from)
+            };
+            let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+            VersionCheck {
+                checked_at: now,
+                etag,
+                last_modified,
+                version: response.json::<Version>().await?,
+            }
+        }
+    };
+
+    if let Err(err) = write(&check, &PATHS.version_check) {
+        debug!("Could not cache version check, {err}");
+    }
+    Ok(check.update_message())
 }
 
 #[derive(Debug, Default)]
@@ -319,8 +925,19 @@ pub struct StartupVars {
     nexus_key: String,
     git_token: String,
     gist_id: String,
+    gist_file: String,
+    gist_public: bool,
+    gist_description: String,
     owner: String,
     repo: String,
+    git_base_url: String,
+    backend: Backend,
+    gitlab_token: String,
+    gitlab_base_url: String,
+    endpoint_url: String,
+    endpoint_token: String,
+    history_limit: Option<usize>,
+    authors: Vec<Author>,
 }
 
 impl StartupVars {
@@ -337,6 +954,7 @@ impl StartupVars {
             git_token: std::env::var(ENV_NAME_GIT)?,
             owner,
             repo,
+            git_base_url: std::env::var(ENV_NAME_GIT_API_URL).unwrap_or_default(),
             ..Default::default()
         })
     }
@@ -348,8 +966,19 @@ impl From<&mut Input> for StartupVars {
             nexus_key: std::mem::take(&mut value.nexus_key),
             git_token: std::mem::take(&mut value.git_token),
             gist_id: std::mem::take(&mut value.gist_id),
+            gist_file: std::mem::take(&mut value.gist_file),
+            gist_public: value.gist_public,
+            gist_description: std::mem::take(&mut value.gist_description),
             owner: std::mem::take(&mut value.owner),
             repo: std::mem::take(&mut value.repo),
+            git_base_url: std::mem::take(&mut value.git_base_url),
+            backend: value.backend,
+            gitlab_token: std::mem::take(&mut value.gitlab_token),
+            gitlab_base_url: std::mem::take(&mut value.gitlab_base_url),
+            endpoint_url: std::mem::take(&mut value.endpoint_url),
+            endpoint_token: std::mem::take(&mut value.endpoint_token),
+            history_limit: value.history_limit,
+            authors: std::mem::take(&mut value.authors),
         }
     }
 }
@@ -360,8 +989,19 @@ impl Input {
             git_token: startup.git_token.clone(),
             nexus_key: startup.nexus_key.clone(),
             gist_id: startup.gist_id.clone(),
+            gist_file: startup.gist_file.clone(),
+            gist_public: startup.gist_public,
+            gist_description: startup.gist_description.clone(),
             owner: startup.owner.clone(),
             repo: startup.repo.clone(),
+            git_base_url: startup.git_base_url.clone(),
+            backend: startup.backend,
+            gitlab_token: startup.gitlab_token.clone(),
+            gitlab_base_url: startup.gitlab_base_url.clone(),
+            endpoint_url: startup.endpoint_url.clone(),
+            endpoint_token: startup.endpoint_token.clone(),
+            history_limit: startup.history_limit,
+            authors: startup.authors.clone(),
             mods,
         }
     }
@@ -371,7 +1011,7 @@ impl Input {
             Ok(data) => Ok(data),
             Err(err) => match err {
                 Error::Io(err) if err.kind() == ErrorKind::NotFound => {
-                    eprintln!(
+                    info!(
                         "Could not find: {INPUT_FILE_NAME}. Continuing with default data structure"
                     );
                     Ok(Input::default())
@@ -381,43 +1021,78 @@ impl Input {
         }
     }
 
-    /// `owner` and `repo` fields are not populated from enviorment variables
+    /// `owner` and `repo` fields are not populated from enviorment variables  
+    /// `git_base_url` is populated from the `GITHUB_API_URL` variable set by GitHub actions  
+    /// `backend` and the gitlab and endpoint fields are optional, as they are only set for their
+    /// respective backend
     fn from_env() -> Result<Self, Error> {
         Ok(Input {
             git_token: std::env::var(ENV_NAME_GIT)?,
             nexus_key: std::env::var(ENV_NAME_NEXUS)?,
             gist_id: std::env::var(ENV_NAME_GIST_ID)?,
-            mods: serde_json::from_str(&std::env::var(ENV_NAME_MODS)?)?,
+            gist_file: std::env::var(ENV_NAME_GIST_FILE).unwrap_or_default(),
+            mods: mods_from_env()?,
+            git_base_url: std::env::var(ENV_NAME_GIT_API_URL).unwrap_or_default(),
+            backend: std::env::var(ENV_NAME_BACKEND)
+                .ok()
+                .and_then(|backend| Backend::from_str(&backend, true).ok())
+                .unwrap_or_default(),
+            gitlab_token: std::env::var(ENV_NAME_GITLAB).unwrap_or_default(),
+            gitlab_base_url: std::env::var(ENV_NAME_GITLAB_API_URL).unwrap_or_default(),
+            endpoint_url: std::env::var(ENV_NAME_ENDPOINT_URL).unwrap_or_default(),
+            endpoint_token: std::env::var(ENV_NAME_ENDPOINT_TOKEN).unwrap_or_default(),
             ..Default::default()
         })
     }
 }
 
-fn prep_io_paths() -> io::Result<()> {
-    let config_dir = PATHS.input.rsplit_once('/').expect("has forward slash").0;
-    if !std::fs::exists(config_dir)? {
-        std::fs::create_dir_all(config_dir)?;
+/// Tracked mods are read from the `TRACKED_MODS` variable, falling back to the file at
+/// `TRACKED_MODS_FILE`
+fn mods_from_env() -> Result<Vec<Mod>, Error> {
+    let non_empty = |name| {
+        std::env::var(name)
+            .ok()
+            .filter(|val| !val.trim().is_empty())
+    };
+
+    if let Some(mods) = non_empty(ENV_NAME_MODS) {
+        return serde_json::from_str(&mods).map_err(Error::from);
+    }
+    match non_empty(ENV_NAME_MODS_FILE) {
+        Some(path) => read(&path),
+        None => Err(Error::Missing(
+            "Tracked mods not found, set the repository variable 'TRACKED_MODS' or 'TRACKED_MODS_FILE'",
+        )),
     }
+}
 
-    #[cfg(any(target_os = "linux", target_os = "macos"))]
-    {
-        let output_dir = PATHS.badges.rsplit_once('/').expect("has forward slash").0;
-        if !std::fs::exists(output_dir)? {
-            std::fs::create_dir_all(output_dir)?;
+fn prep_io_paths() -> io::Result<()> {
+    for path in [
+        &PATHS.input,
+        &PATHS.output,
+        &PATHS.badges,
+        &PATHS.preferences,
+        &PATHS.history,
+        &PATHS.version_check,
+        &PATHS.remote_cache,
+    ] {
+        let Some(dir) = Path::new(path.as_ref()).parent() else {
+            continue;
+        };
+        if !dir.as_os_str().is_empty() && !std::fs::exists(dir)? {
+            std::fs::create_dir_all(dir)?;
         }
     }
     Ok(())
 }
 
-pub fn startup(on_remote: bool) -> Result<Vec<Mod>, Error> {
-    if !on_remote {
-        tokio::task::spawn(async {
-            match check_program_version().await {
-                Ok(Some(msg)) => println!("{msg}"),
-                Ok(None) => (),
-                Err(err) => eprintln!("{err}"),
-            }
-        });
+pub fn startup(on_remote: bool, check_version: bool) -> Result<Vec<Mod>, Error> {
+    if !on_remote && check_version {
+        let task = tokio::task::spawn(tokio::time::timeout(
+            VERSION_CHECK_TIMEOUT,
+            check_program_version(false),
+        ));
+        *VERSION_CHECK.lock().expect("lock is never poisoned") = Some(task);
     }
 
     prep_io_paths()?;
@@ -432,6 +1107,21 @@ pub fn startup(on_remote: bool) -> Result<Vec<Mod>, Error> {
     Ok(input.mods)
 }
 
+/// Reports the result of the version check spawned in [`startup`], call once the command's own
+/// output is complete. Waits no longer than `VERSION_CHECK_TIMEOUT` from when the check started
+pub async fn finish_version_check() {
+    let Some(task) = VERSION_CHECK.lock().expect("lock is never poisoned").take() else {
+        return;
+    };
+    match task.await {
+        Ok(Ok(Ok(Some(msg)))) => info!("{msg}"),
+        Ok(Ok(Ok(None))) => (),
+        Ok(Ok(Err(err))) => warn!("{err}"),
+        Ok(Err(_)) => debug!("Version check timed out"),
+        Err(err) => debug!("Version check did not complete, {err}"),
+    }
+}
+
 pub fn read<T: for<'de> Deserialize<'de>>(path: &str) -> Result<T, Error> {
     let file = File::open(path)?;
     let reader = BufReader::new(file);
@@ -439,55 +1129,450 @@ pub fn read<T: for<'de> Deserialize<'de>>(path: &str) -> Result<T, Error> {
     Ok(data)
 }
 
+/// Serializes `data` into a temporary file next to `path` then renames it over `path`, so a crash or
+/// failed write can never leave a truncated file behind. The permissions of an existing file at
+/// `path` are kept. When `path` is the input file its previous contents are first copied to
+/// [`FilePaths::input_backup`]
 pub fn write<T: Serialize>(data: T, path: &str) -> Result<(), Error> {
-    let file = File::create(path)?;
-    serde_json::to_writer_pretty(file, &data)?;
-    Ok(())
+    let temp_path = format!("{path}.tmp");
+
+    let res = (|| {
+        let mut writer = BufWriter::new(File::create(&temp_path)?);
+        serde_json::to_writer_pretty(&mut writer, &data)?;
+        let file = writer
+            .into_inner()
+            .map_err(io::IntoInnerError::into_error)?;
+        file.sync_all()?;
+        if let Ok(metadata) = std::fs::metadata(path) {
+            std::fs::set_permissions(&temp_path, metadata.permissions())?;
+        }
+        if path == PATHS.input {
+            backup_input(&temp_path);
+        }
+        std::fs::rename(&temp_path, path).map_err(Error::from)
+    })();
+
+    if res.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    res
+}
+
+/// Copies the current input file to the backup location, unless the input file does not exist yet or
+/// `replacement` holds the same contents. Failing to backup does not stop the write
+fn backup_input(replacement: &str) {
+    let Ok(prev) = std::fs::read(PATHS.input.as_ref()) else {
+        return;
+    };
+    if std::fs::read(replacement).is_ok_and(|new| new == prev) {
+        return;
+    }
+    let backup = PATHS.input_backup();
+    if let Err(err) = std::fs::copy(PATHS.input.as_ref(), &backup) {
+        warn!(
+            "{err}, previous contents of {} were not backed up",
+            PATHS.input
+        );
+    }
+}
+
+/// Url badges read counts from as cached by a previous run, `None` if it was cached for another gist
+fn cached_universal_url() -> Option<String> {
+    let cache = read::<RemoteCache>(&PATHS.remote_cache).ok()?;
+    (cache.gist_id == VARS.get().expect("set on startup").gist_id).then_some(cache.universal_url)
+}
+
+fn cache_universal_url(gist_id: &str, universal_url: &str) {
+    if let Ok(cache) = read::<RemoteCache>(&PATHS.remote_cache) {
+        if cache.gist_id == gist_id && cache.universal_url == universal_url {
+            return;
+        }
+    }
+    let cache = RemoteCache {
+        gist_id: gist_id.to_string(),
+        universal_url: universal_url.to_string(),
+    };
+    if let Err(err) = write(&cache, &PATHS.remote_cache) {
+        debug!("Could not cache the remote url, {err}");
+    }
+}
+
+fn append_history(output: &BTreeMap<String, ModDetails>) -> Result<(), Error> {
+    let mut history = match read::<VecDeque<Snapshot>>(&PATHS.history) {
+        Ok(history) => history,
+        Err(Error::Io(err)) if err.kind() == ErrorKind::NotFound => VecDeque::new(),
+        Err(err) => return Err(err),
+    };
+
+    history.push_back(Snapshot::from(output));
+
+    let limit = VARS
+        .get()
+        .expect("set on startup")
+        .history_limit
+        .unwrap_or(DEFAULT_HISTORY_LIMIT);
+    if history.len() > limit {
+        history.drain(..history.len() - limit);
+    }
+
+    write(history, &PATHS.history)
+}
+
+/// Formats `n` with a comma between every group of three digits
+fn group_thousands(n: usize) -> String {
+    let digits = n.to_string();
+    let mut output = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, ch) in digits.chars().enumerate() {
+        if i != 0 && (digits.len() - i).is_multiple_of(3) {
+            output.push(',');
+        }
+        output.push(ch);
+    }
+    output
 }
 
-fn write_badges(output: BTreeMap<String, ModDetails>, universal_url: &str) -> Result<(), Error> {
-    let file = File::create(PATHS.badges.as_ref())?;
-    let mut writer = BufWriter::new(file);
+/// Shortens `n` the way shields.io metric badges do, e.g. `12345` to `12.3k`
+fn compact_count(n: usize) -> String {
+    const UNITS: [&str; 4] = ["", "k", "M", "B"];
 
-    let badge_prefs = read::<BadgePreferences>(&PATHS.preferences).unwrap_or_else(|err| {
-        if !matches!(&err, Error::Io(err) if err.kind() == ErrorKind::NotFound) {
-            eprintln!("{err}, using default styling")
+    let (mut value, mut unit) = (n as f64, 0);
+    // Compared against the rounding threshold so `999_950` becomes `1M` rather than `1000k`
+    while value >= 999.95 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        return n.to_string();
+    }
+    let value = format!("{value:.1}");
+    format!("{}{}", value.trim_end_matches(".0"), UNITS[unit])
+}
+
+/// Returns the sign and magnitude of the change from `prev` to `curr`
+fn signed_diff(prev: usize, curr: usize) -> (char, usize) {
+    if curr >= prev {
+        ('+', curr - prev)
+    } else {
+        ('-', prev - curr)
+    }
+}
+
+/// Prints the change in total downloads of each entry in `curr` compared to `prev`
+fn print_deltas(prev: Option<&BTreeMap<String, ModDetails>>, curr: &BTreeMap<String, ModDetails>) {
+    println!("Download count changes since last run:");
+    for (uid, entry) in curr {
+        let Some(prev_entry) = prev.and_then(|prev| prev.get(uid)) else {
+            println!(
+                "- {}: {} (baseline)",
+                entry.name,
+                group_thousands(entry.mod_downloads)
+            );
+            continue;
+        };
+        let (sign, diff) = signed_diff(prev_entry.mod_downloads, entry.mod_downloads);
+        print!("- {}: {sign}{}", entry.name, group_thousands(diff));
+        if prev_entry.mod_downloads != 0 {
+            print!(
+                " ({sign}{:.1}%)",
+                diff as f64 / prev_entry.mod_downloads as f64 * 100.0
+            );
         }
-        BadgePreferences::default()
+        println!();
+    }
+}
+
+/// Appends a markdown table of the run to `$GITHUB_STEP_SUMMARY` and sets the `updated`,
+/// `total_downloads`, `total_unique_downloads`, `mods`, and `url` step outputs in `$GITHUB_OUTPUT`.
+/// Either file is skipped when its variable is not set
+fn write_actions_summary(output: &Output, updated: bool, universal_url: &str) -> io::Result<()> {
+    const ENV_NAME_SUMMARY: &str = "GITHUB_STEP_SUMMARY";
+    const ENV_NAME_OUTPUT: &str = "GITHUB_OUTPUT";
+
+    fn append(path: &str, content: &str) -> io::Result<()> {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(content.as_bytes())
+    }
+
+    let totals = output.mods.get(TOTAL_KEY);
+    let (total, total_unique) = totals.map_or((0, 0), |entry| {
+        (entry.mod_downloads, entry.mod_unique_downloads)
     });
 
-    let encoded_fields = EncodedFields::new(universal_url, &badge_prefs, URL_ENCODE_SET);
-
-    writeln!(writer, "# Shields.io Badges via Nexus Badges")?;
-    writeln!(writer, "Base template: {BADGE_URL}")?;
-    writeln!(writer, "Data source URL: {universal_url}")?;
-    writeln!(writer, "{badge_prefs}")?;
-
-    for (uid, entry) in output.into_iter() {
-        let query = format!("$.{uid}.{}", badge_prefs.count.field_name());
-        writeln!(writer, "## {}", entry.name)?;
-        badge_prefs.format.write_badge(
-            &mut writer,
-            URL_ENCODE_SET,
-            &encoded_fields,
-            &query,
-            &entry.url,
+    if let Ok(path) = std::env::var(ENV_NAME_SUMMARY) {
+        let mut summary = format!(
+            "### Nexus Badges\n\nRemote {} updated\n\n\
+            | Mod | Downloads | Unique downloads |\n\
+            | --- | ---: | ---: |\n",
+            if updated { "was" } else { "was not" }
+        );
+        for (_, entry) in output.mods.iter().filter(|(key, _)| *key != TOTAL_KEY) {
+            let name = entry.name.replace('|', "\\|");
+            let name = if entry.url.is_empty() {
+                name
+            } else {
+                format!("[{name}]({})", entry.url)
+            };
+            summary.push_str(&format!(
+                "| {name} | {} | {} |\n",
+                group_thousands(entry.mod_downloads),
+                group_thousands(entry.mod_unique_downloads)
+            ));
+        }
+        if totals.is_some() {
+            summary.push_str(&format!(
+                "| **{TOTAL_KEY}** | **{}** | **{}** |\n",
+                group_thousands(total),
+                group_thousands(total_unique)
+            ));
+        }
+        append(&path, &summary)?;
+    }
+
+    if let Ok(path) = std::env::var(ENV_NAME_OUTPUT) {
+        append(
+            &path,
+            &format!(
+                "updated={updated}\ntotal_downloads={total}\ntotal_unique_downloads={total_unique}\n\
+                mods={}\nurl={universal_url}\n",
+                output.mods.len() - usize::from(totals.is_some())
+            ),
         )?;
+    }
+    Ok(())
+}
+
+/// Badges are saved with the extension of the selected format unless a path was given with `--badges`
+fn badges_path(format: BadgeFormat) -> Cow<'static, str> {
+    if PATH_OVERRIDES
+        .get()
+        .is_some_and(|overrides| overrides.badges.is_some())
+    {
+        return PATHS.badges.clone();
+    }
+    Cow::Owned(
+        Path::new(PATHS.badges.as_ref())
+            .with_extension(format.file_extension())
+            .to_string_lossy()
+            .into_owned(),
+    )
+}
+
+/// A missing file yields the default preferences, a malformed file is an error so that it is never
+/// silently replaced with defaults
+fn read_badge_prefs() -> Result<BadgePreferences, Error> {
+    match read::<BadgePreferences>(&PATHS.preferences) {
+        Ok(prefs) => Ok(prefs),
+        Err(Error::Io(err)) if err.kind() == ErrorKind::NotFound => Ok(BadgePreferences::default()),
+        Err(Error::SerdeJson(err)) => Err(Error::Io(io::Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Malformed badge preferences in: {}, {err}. Fix or delete the file to continue",
+                PATHS.preferences
+            ),
+        ))),
+        Err(err) => Err(err),
+    }
+}
+
+/// Preferences are read from disk once per run, so every badge rendered in a run shares the same state
+fn badge_prefs() -> Result<&'static BadgePreferences, Error> {
+    if let Some(prefs) = BADGE_PREFS.get() {
+        return Ok(prefs);
+    }
+    let prefs = read_badge_prefs()?;
+    Ok(BADGE_PREFS.get_or_init(|| prefs))
+}
+
+/// Must be called before [`badge_prefs`] is first accessed for `prefs` to be used
+fn set_badge_prefs(prefs: BadgePreferences) {
+    let res = BADGE_PREFS.set(prefs);
+    debug_assert!(res.is_ok(), "set before first read");
+}
+
+fn write_badges(output: Output, universal_url: &str) -> Result<(), Error> {
+    let badge_prefs = badge_prefs()?;
+    let path = badges_path(badge_prefs.format);
+    let file = File::create(path.as_ref())?;
+    render_badges(
+        BufWriter::new(file),
+        badge_prefs,
+        output,
+        universal_url,
+        false,
+    )?;
+    info!("Badges saved to: {path}");
+    Ok(())
+}
+
+fn print_badges(output: Output, universal_url: &str) -> Result<(), Error> {
+    let color = color_enabled(&io::stdout());
+    render_badges(
+        io::stdout().lock(),
+        badge_prefs()?,
+        output,
+        universal_url,
+        color,
+    )
+}
+
+fn render_badges(
+    mut writer: impl Write,
+    badge_prefs: &BadgePreferences,
+    output: Output,
+    universal_url: &str,
+    color: bool,
+) -> Result<(), Error> {
+    let encoded_fields = EncodedFields::new(universal_url, badge_prefs, URL_ENCODE_SET);
+    let total_fields = EncodedFields::new_total(universal_url, badge_prefs, URL_ENCODE_SET);
+
+    let json = badge_prefs.format.is_json();
+    if !json {
+        writeln!(writer, "# Shields.io Badges via Nexus Badges")?;
+        writeln!(writer, "Base template: {BADGE_URL}")?;
+        writeln!(writer, "Data source URL: {universal_url}")?;
+        writeln!(writer, "{}", badge_prefs.styled(color))?;
+    }
+
+    let mut entries = output.mods.into_iter().collect::<Vec<_>>();
+    let total = entries
+        .iter()
+        .position(|(uid, _)| uid == TOTAL_KEY)
+        .map(|i| entries.remove(i))
+        .filter(|_| badge_prefs.count.has_total());
+
+    badge_prefs.sort.sort(&mut entries);
+
+    if let Some(total) = total {
+        match badge_prefs.total_position {
+            TotalPosition::First => entries.insert(0, total),
+            TotalPosition::Last => entries.push(total),
+        }
+    }
+
+    let table = badge_prefs.format.is_table();
+    if table {
+        BadgeFormat::write_table_header(&mut writer)?;
+    }
+
+    if badge_prefs.updated_badge {
+        let updated = ModDetails {
+            name: String::from("Last updated"),
+            label: Some(String::from("updated")),
+            ..Default::default()
+        };
+        entries.push((UPDATED_KEY.to_string(), updated));
+    }
+
+    let mut badge_urls = serde_json::Map::new();
+
+    for (uid, entry) in entries.into_iter() {
+        let link = if badge_prefs.link {
+            entry.url.as_str()
+        } else {
+            ""
+        };
+        let query = if uid == UPDATED_KEY {
+            format!("$.{UPDATED_KEY}")
+        } else {
+            format!("$.mods.{uid}.{}", badge_prefs.count.field_name())
+        };
+        let mod_fields = entry
+            .label
+            .as_deref()
+            .map(|label| encoded_fields.with_label(label, URL_ENCODE_SET));
+        let fields = if uid == TOTAL_KEY {
+            &total_fields
+        } else {
+            mod_fields.as_ref().unwrap_or(&encoded_fields)
+        };
+        if json {
+            let badge_url = badge_prefs
+                .format
+                .badge_url(URL_ENCODE_SET, fields, &query, link);
+            let name = if badge_urls.contains_key(&entry.name) {
+                format!("{} ({uid})", entry.name)
+            } else {
+                entry.name
+            };
+            badge_urls.insert(name, badge_url.into());
+            continue;
+        }
+        if table {
+            BadgeFormat::write_table_row(
+                &mut writer,
+                URL_ENCODE_SET,
+                fields,
+                &query,
+                &entry.name,
+                link,
+            )?;
+            continue;
+        }
+        if entry.game.is_empty() {
+            writeln!(writer, "## {}", entry.name)?;
+        } else {
+            writeln!(writer, "## {} - {}", entry.game, entry.name)?;
+        }
+        badge_prefs
+            .format
+            .write_badge(&mut writer, URL_ENCODE_SET, fields, &query, link)?;
         writeln!(writer)?;
         writeln!(writer, "Configuration:")?;
         writeln!(writer, "- Query: {query}")?;
-        if !entry.url.is_empty() {
-            writeln!(writer, "- Link: {}", entry.url)?;
+        if let Some(ref label) = entry.label {
+            writeln!(writer, "- Label: {label}")?;
+        }
+        if !link.is_empty() {
+            writeln!(writer, "- Link: {link}")?;
         }
         writeln!(writer)?;
     }
 
-    writer.flush()?;
+    if table {
+        writeln!(writer, "```")?;
+    }
 
-    println!("Badges saved to: {}", PATHS.badges);
+    if json {
+        serde_json::to_writer_pretty(&mut writer, &badge_urls)?;
+        writeln!(writer)?;
+    }
+
+    writer.flush()?;
     Ok(())
 }
 
+/// Prompts the user with a yes/no question, anything other than 'y' or 'yes' is treated as no
+pub fn confirm(prompt: &str) -> io::Result<bool> {
+    print!("{prompt} [y/N]: ");
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(matches!(
+        input.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
+/// Prompts the user for a line of input, returns `None` if the input was left blank
+pub fn prompt(label: &str, hidden: bool) -> io::Result<Option<String>> {
+    print!("{label}: ");
+    io::stdout().flush()?;
+    let input = if hidden {
+        rpassword::read_password()?
+    } else {
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input)? == 0 {
+            return Err(io::Error::new(ErrorKind::UnexpectedEof, "Input closed"));
+        }
+        input
+    };
+    let input = input.trim();
+    Ok((!input.is_empty()).then(|| input.to_string()))
+}
+
 pub fn await_user_for_end(on_remote: bool) {
     if !on_remote {
         println!("Press enter to exit...");
@@ -542,3 +1627,51 @@ pub async fn conditional_join<T1, T2>(
         (None, None) => (None, None),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::ser::{Error as _, SerializeStruct, Serializer};
+
+    /// Writes part of a struct before failing, like a serializer error mid write would
+    struct FailsMidway;
+
+    impl Serialize for FailsMidway {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut state = serializer.serialize_struct("FailsMidway", 2)?;
+            state.serialize_field("written", &1)?;
+            Err(S::Error::custom("failed midway"))
+        }
+    }
+
+    /// Points `PATHS` at a directory of its own, every test that reaches `PATHS` must call this
+    /// first as the paths are resolved once per test binary
+    pub(crate) fn temp_config_dir() -> &'static Path {
+        PATH_OVERRIDES
+            .get_or_init(|| {
+                let dir =
+                    std::env::temp_dir().join(format!("nexus_badges_test_{}", std::process::id()));
+                std::fs::create_dir_all(&dir).unwrap();
+                PathOverrides {
+                    dir: Some(dir),
+                    ..Default::default()
+                }
+            })
+            .dir
+            .as_deref()
+            .expect("set above")
+    }
+
+    #[test]
+    fn failed_write_keeps_original() {
+        let path = temp_config_dir().join("write.json");
+        let path = path.to_str().unwrap();
+
+        write(serde_json::json!({ "original": true }), path).unwrap();
+        let original = std::fs::read(path).unwrap();
+
+        assert!(write(FailsMidway, path).is_err());
+        assert_eq!(std::fs::read(path).unwrap(), original);
+        assert!(!Path::new(&format!("{path}.tmp")).exists());
+    }
+}
@@ -2,37 +2,108 @@ use clap::Parser;
 use nexus_badges::{
     await_user_for_end,
     commands::{
-        init_actions, init_remote, process, update_args_local, update_args_remote,
-        update_cache_key, version, Modify,
+        delete_gist, dump_counts, export_mods, init_actions, init_remote, list_mods,
+        print_completions, print_stats, print_workflow_status, process, refresh, restore_input,
+        set_repo_vars, setup_credentials, setup_remote, update_args_local, update_args_remote,
+        update_cache_key, validate, version, write_badges_only, write_manpages, Modify,
     },
-    exit_on_remote,
+    exit_on_remote, finish_version_check, init_client, init_logger,
     models::{
-        cli::{Cli, Commands},
+        cli::{Cli, Commands, Workflow},
         error::Error,
+        json_data::Author,
     },
-    print_err, return_after,
-    services::git::set_workflow_state,
-    startup, unsupported,
+    print_err, read_certificates, report_err, return_after,
+    services::{git::set_workflow_state, nexus::set_skip_errors},
+    set_color_choice, set_output_mode, set_path_overrides, startup, unsupported, ClientOptions,
+    PathOverrides,
 };
+use std::time::Duration;
 
 #[tokio::main]
 async fn main() {
     let mut cli = Cli::parse();
+    let output_mode = cli.output_mode();
+    set_output_mode(output_mode);
+    init_logger(cli.log_level());
+
+    set_path_overrides(PathOverrides {
+        dir: cli.config_dir.take(),
+        input: cli.input.take(),
+        output: cli.output.take(),
+        badges: cli.badges.take(),
+    });
+
+    let root_certificates = match cli.ca_cert.as_deref().map(read_certificates).transpose() {
+        Ok(certs) => certs.unwrap_or_default(),
+        Err(err) => {
+            report_err(&err);
+            exit_on_remote(cli.remote, 1);
+            return;
+        }
+    };
+    if let Err(err) = init_client(ClientOptions {
+        timeout: Duration::from_secs(cli.timeout),
+        proxy: cli.proxy.take(),
+        root_certificates,
+        accept_invalid_certs: cli.danger_insecure,
+    }) {
+        report_err(&err);
+        exit_on_remote(cli.remote, 1);
+        return;
+    }
+    set_skip_errors(cli.skip_errors);
+    set_color_choice(cli.color_choice());
 
     if let Some(ref mut command) = cli.command {
         match command {
             Commands::Version => {
-                return_after!(version(cli.remote).await, cli.remote);
+                return_after!(
+                    version(cli.remote, !cli.no_version_check, output_mode).await,
+                    cli.remote
+                );
+            }
+            Commands::Completions { shell } => {
+                unsupported!(command, on_remote, cli.remote);
+                print_completions(*shell);
+                return;
+            }
+            Commands::Manpage { out } => {
+                unsupported!(command, on_remote, cli.remote);
+                print_err!(write_manpages(out.as_deref()));
+                return;
+            }
+            Commands::Restore => {
+                unsupported!(command, on_remote, cli.remote);
+                print_err!(restore_input());
+                return;
+            }
+            Commands::UpdateCacheKey { old, prefix, new } => {
+                unsupported!(command, on_local, cli.remote);
+                return_after!(
+                    update_cache_key(old.as_deref(), prefix.as_deref(), new).await,
+                    cli.remote
+                );
             }
-            Commands::UpdateCacheKey { old, new } => {
+            Commands::SetRepoVar { vars, delete_cache } => {
                 unsupported!(command, on_local, cli.remote);
-                return_after!(update_cache_key(old.as_deref(), new).await, cli.remote);
+                return_after!(
+                    set_repo_vars(std::mem::take(vars), delete_cache.as_deref()).await,
+                    cli.remote
+                );
+            }
+            Commands::Setup => {
+                unsupported!(command, on_remote, cli.remote);
+                if let Err(err) = setup_credentials().await {
+                    report_err(&err);
+                    return;
+                }
             }
             Commands::SetArg(args) => {
                 unsupported!(command, on_remote, cli.remote);
                 if let Err(err) = update_args_local(args).await {
-                    if !matches!(err, Error::NotSetup(_)) {
-                        eprintln!("{err}")
+                    if output_mode.is_json() || !matches!(err, Error::NotSetup(_)) {
+                        report_err(&err)
                     }
                     return;
                 }
@@ -44,12 +115,12 @@ async fn main() {
         }
     }
 
-    let input_mods = match startup(cli.remote) {
+    let input_mods = match startup(cli.remote, !cli.no_version_check) {
         Ok(data) => data,
         Err(err) => {
-            eprintln!("{err}");
+            report_err(&err);
             exit_on_remote(cli.remote, 1);
-            await_user_for_end(cli.remote);
+            await_user_for_end(cli.remote || output_mode.is_json());
             return;
         }
     };
@@ -57,21 +128,92 @@ async fn main() {
     if let Some(command) = cli.command {
         unsupported!(command, on_remote, cli.remote);
         match command {
-            Commands::SetArg(args) => print_err!(update_args_remote(args).await),
-            Commands::Add(details) => print_err!(input_mods.add_mod(details).await),
-            Commands::Remove(details) => print_err!(input_mods.remove_mod(details).await),
+            Commands::SetArg(args) => print_err!(update_args_remote(*args).await),
+            Commands::Add(args) => match args.into_mod() {
+                Ok(details) => print_err!(input_mods.add_mod(details, output_mode).await),
+                Err(args) => print_err!(input_mods.add_batch(*args, output_mode).await),
+            },
+            Commands::AddAuthor { domain, author } => print_err!(
+                input_mods
+                    .add_author(
+                        Author {
+                            domain,
+                            name: author
+                        },
+                        output_mode
+                    )
+                    .await
+            ),
+            Commands::Remove(details) => {
+                print_err!(input_mods.remove_mod(details, output_mode).await)
+            }
+            Commands::List => list_mods(&input_mods, output_mode),
+            Commands::Stats { since } => print_err!(print_stats(since, output_mode)),
+            Commands::Badges => print_err!(write_badges_only(&input_mods, false).await),
+            Commands::Refresh { push } => print_err!(refresh(input_mods, push, output_mode).await),
+            Commands::Export { out } => print_err!(export_mods(&input_mods, &out)),
+            Commands::Dump { out, format } => {
+                print_err!(dump_counts(input_mods, format, out.as_deref()).await)
+            }
+            Commands::Import { path, merge } => {
+                print_err!(input_mods.import_mods(&path, merge).await)
+            }
+            Commands::Setup => print_err!(setup_remote(input_mods).await),
+            Commands::Validate => {
+                if !validate(output_mode).await {
+                    std::process::exit(1)
+                }
+            }
             Commands::Init => print_err!(init_remote(input_mods).await),
+            Commands::DeleteGist { yes } => print_err!(delete_gist(input_mods, yes).await),
             Commands::InitActions => print_err!(init_actions(input_mods).await),
+            Commands::Automation {
+                state: Workflow::Status,
+            } => print_err!(print_workflow_status(output_mode).await),
             Commands::Automation { state } => print_err!(set_workflow_state(state).await),
-            Commands::UpdateCacheKey { old: _, new: _ } => unreachable!("by repo-variable guard"),
+            Commands::UpdateCacheKey {
+                old: _,
+                prefix: _,
+                new: _,
+            } => unreachable!("by repo-variable guard"),
+            Commands::SetRepoVar {
+                vars: _,
+                delete_cache: _,
+            } => unreachable!("by set-repo-var guard"),
+            Commands::Completions { shell: _ } => unreachable!("by completions guard"),
+            Commands::Manpage { out: _ } => unreachable!("by manpage guard"),
+            Commands::Restore => unreachable!("by restore guard"),
             Commands::Version => unreachable!("by version guard"),
         }
+        finish_version_check().await;
         return;
     }
 
-    process(input_mods, cli.remote).await.unwrap_or_else(|err| {
-        eprintln!("{err}");
+    if cli.badges_only {
+        let flag = "--badges-only";
+        unsupported!(flag, on_remote, cli.remote);
+        print_err!(write_badges_only(&input_mods, true).await);
+        finish_version_check().await;
+        return;
+    }
+
+    let updated = process(
+        input_mods,
+        cli.remote,
+        cli.dry_run,
+        cli.no_badges,
+        output_mode,
+    )
+    .await
+    .unwrap_or_else(|err| {
+        report_err(&err);
         exit_on_remote(cli.remote, 1);
+        false
     });
-    await_user_for_end(cli.remote);
+    finish_version_check().await;
+
+    // Lets scheduled workflows tell a run that left the gist unchanged apart from one that failed
+    exit_on_remote(cli.remote && cli.exit_unchanged && !updated, 75);
+    // Waiting on stdin would hang scripts that pipe the JSON result
+    await_user_for_end(cli.remote || output_mode.is_json());
 }
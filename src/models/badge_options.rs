@@ -1,7 +1,9 @@
+use crate::models::json_data::ModDetails;
 use clap::ValueEnum;
 use percent_encoding::{percent_encode, AsciiSet, PercentEncode};
 use serde::{Deserialize, Deserializer, Serialize};
 use std::{
+    cmp::Reverse,
     fmt::{Debug, Display},
     str::FromStr,
 };
@@ -13,35 +15,100 @@ pub struct BadgePreferences {
     pub format: BadgeFormat,
     pub count: DownloadCount,
     pub label: String,
-    #[serde(deserialize_with = "deserialize_color")]
+    /// Label color of every badge, or only badges shown in dark mode when `label_color_light` is set
+    #[serde(
+        rename = "label_color_dark",
+        alias = "label_color",
+        deserialize_with = "deserialize_color"
+    )]
     #[serde(skip_serializing_if = "Color::is_none")]
     pub label_color: Color,
+    /// When set markdown badges are written as a pair of images shown in GitHub's dark and light
+    /// modes respectively
+    #[serde(deserialize_with = "deserialize_color")]
+    #[serde(skip_serializing_if = "Color::is_none")]
+    pub label_color_light: Color,
     #[serde(deserialize_with = "deserialize_color")]
     #[serde(skip_serializing_if = "Color::is_none")]
     pub color: Color,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logo: Option<String>,
+    #[serde(deserialize_with = "deserialize_color")]
+    #[serde(skip_serializing_if = "Color::is_none")]
+    pub logo_color: Color,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logo_width: Option<u32>,
+    /// Written as `null` when unset, a missing field is read as the default
+    pub cache_seconds: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_label: Option<String>,
+    #[serde(deserialize_with = "deserialize_color")]
+    #[serde(skip_serializing_if = "Color::is_none")]
+    pub total_color: Color,
+    pub sort: SortBy,
+    pub total_position: TotalPosition,
+    pub updated_badge: bool,
+    /// Badges link to the mod's Nexus page when set
+    pub link: bool,
 }
 
+pub const MIN_CACHE_SECONDS: u32 = 300;
+pub const MAX_LOGO_WIDTH: u32 = 100;
+const DEFAULT_CACHE_SECONDS: u32 = 3600;
+
 impl BadgePreferences {
-    fn encode_optionals(&self, ascii_set: &'static AsciiSet) -> String {
+    fn encode_optionals(
+        &self,
+        label_color: &Color,
+        color: &Color,
+        ascii_set: &'static AsciiSet,
+    ) -> String {
         let mut output = String::new();
         if let Some(style) = self.style() {
             output.push_str(&format!("&style={style}"));
         }
-        if let Some(ref color) = self.label_color.0 {
+        if let Some(ref color) = label_color.0 {
             output.push_str(&format!(
                 "&labelColor={}",
                 percent_encode(color.as_bytes(), ascii_set)
             ));
         }
-        if let Some(ref color) = self.color.0 {
+        if let Some(ref color) = color.0 {
             output.push_str(&format!(
                 "&color={}",
                 percent_encode(color.as_bytes(), ascii_set)
             ));
         }
+        if let Some(ref logo) = self.logo {
+            output.push_str(&format!(
+                "&logo={}",
+                percent_encode(logo.as_bytes(), ascii_set)
+            ));
+            if let Some(ref color) = self.logo_color.0 {
+                output.push_str(&format!(
+                    "&logoColor={}",
+                    percent_encode(color.as_bytes(), ascii_set)
+                ));
+            }
+            if let Some(width) = self.logo_width {
+                output.push_str(&format!("&logoWidth={width}"));
+            }
+        }
+        if let Some(seconds) = self.cache_seconds {
+            output.push_str(&format!("&cacheSeconds={seconds}"));
+        }
         output
     }
 
+    fn encode_light_optionals(
+        &self,
+        color: &Color,
+        ascii_set: &'static AsciiSet,
+    ) -> Option<String> {
+        (!self.label_color_light.is_none())
+            .then(|| self.encode_optionals(&self.label_color_light, color, ascii_set))
+    }
+
     #[inline]
     pub fn set_style(&mut self, style: BadgeStyle) {
         self.style = style
@@ -63,24 +130,110 @@ impl Default for BadgePreferences {
             format: BadgeFormat::default(),
             count: DownloadCount::default(),
             label_color: Color::default(),
+            label_color_light: Color::default(),
             color: Color::default(),
+            logo: None,
+            logo_color: Color::default(),
+            logo_width: None,
+            cache_seconds: Some(DEFAULT_CACHE_SECONDS),
+            total_label: None,
+            total_color: Color::default(),
+            sort: SortBy::default(),
+            total_position: TotalPosition::default(),
+            updated_badge: false,
+            link: true,
         }
     }
 }
 
-impl Display for BadgePreferences {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl BadgePreferences {
+    /// Same listing as `Display`, with ANSI colored values when `color` is set. Hex colors are
+    /// followed by a swatch of the color itself
+    pub fn styled(&self, color: bool) -> StyledPreferences<'_> {
+        StyledPreferences { prefs: self, color }
+    }
+
+    fn write_list(&self, f: &mut std::fmt::Formatter<'_>, ansi: bool) -> std::fmt::Result {
+        let value = |val: &dyn Display| {
+            if ansi {
+                format!("{ANSI_VALUE}{val}{ANSI_RESET}")
+            } else {
+                val.to_string()
+            }
+        };
+        let color = |color: &Color| match color.rgb().filter(|_| ansi) {
+            Some((r, g, b)) => format!(
+                "{} {ANSI_SWATCH_START}{r};{g};{b}m{SWATCH}{ANSI_RESET}",
+                value(color)
+            ),
+            None => value(color),
+        };
+
         writeln!(f, "Style preferences:")?;
-        writeln!(f, "- Label: {}", self.label)?;
-        writeln!(f, "- Count: {}", self.count)?;
-        writeln!(f, "- Style: {}", self.style)?;
-        writeln!(f, "- Format: {}", self.format)?;
-        writeln!(f, "- Label color: {}", self.label_color)?;
-        writeln!(f, "- Color: {}", self.color)?;
+        writeln!(f, "- Label: {}", value(&self.label))?;
+        writeln!(f, "- Count: {}", value(&self.count))?;
+        writeln!(f, "- Style: {}", value(&self.style))?;
+        writeln!(f, "- Format: {}", value(&self.format))?;
+        writeln!(f, "- Label color: {}", color(&self.label_color))?;
+        writeln!(
+            f,
+            "- Light mode label color: {}",
+            color(&self.label_color_light)
+        )?;
+        writeln!(f, "- Color: {}", color(&self.color))?;
+        match self.logo.as_deref() {
+            Some(logo) if logo.starts_with("data:") => {
+                writeln!(f, "- Logo: {}", value(&"embedded image"))?
+            }
+            Some(logo) => writeln!(f, "- Logo: {}", value(&logo))?,
+            None => writeln!(f, "- Logo: {}", value(&"none"))?,
+        }
+        writeln!(f, "- Logo color: {}", color(&self.logo_color))?;
+        match self.logo_width {
+            Some(width) => writeln!(f, "- Logo width: {}", value(&width))?,
+            None => writeln!(f, "- Logo width: {}", value(&"default"))?,
+        }
+        match self.cache_seconds {
+            Some(seconds) => writeln!(f, "- Cache seconds: {}", value(&seconds))?,
+            None => writeln!(f, "- Cache seconds: {}", value(&"shields.io default"))?,
+        }
+        writeln!(
+            f,
+            "- Total label: {}",
+            value(&self.total_label.as_deref().unwrap_or(&self.label))
+        )?;
+        writeln!(f, "- Total color: {}", color(&self.total_color))?;
+        writeln!(f, "- Sort: {}", value(&self.sort))?;
+        writeln!(f, "- Total position: {}", value(&self.total_position))?;
+        writeln!(f, "- Last updated badge: {}", value(&self.updated_badge))?;
+        writeln!(f, "- Link to mod page: {}", value(&self.link))?;
         Ok(())
     }
 }
 
+const ANSI_VALUE: &str = "\x1b[1;36m";
+const ANSI_SWATCH_START: &str = "\x1b[38;2;";
+const ANSI_RESET: &str = "\x1b[0m";
+const SWATCH: &str = "\u{2588}\u{2588}";
+
+impl Display for BadgePreferences {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_list(f, false)
+    }
+}
+
+/// [`BadgePreferences`] listing meant for a terminal, see [`BadgePreferences::styled`]
+pub struct StyledPreferences<'a> {
+    prefs: &'a BadgePreferences,
+    color: bool,
+}
+
+impl Display for StyledPreferences<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.prefs.write_list(f, self.color)
+    }
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug, Default)]
 pub struct Color(Option<String>);
 
@@ -89,6 +242,13 @@ impl Color {
     pub fn is_none(&self) -> bool {
         self.0.is_none()
     }
+
+    /// Components of a hex color, named colors are rendered by shields.io so have no value here
+    fn rgb(&self) -> Option<(u8, u8, u8)> {
+        let hex = self.0.as_deref()?.strip_prefix('#')?;
+        let component = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        Some((component(0)?, component(2)?, component(4)?))
+    }
 }
 
 impl Display for Color {
@@ -109,11 +269,32 @@ where
         }
     };
     Ok(Color::from_str(&s).unwrap_or_else(|err| {
-        eprintln!("'{s}' is not a valid hex color. Using default color.\n{err}");
+        eprintln!("'{s}' is not a valid color. Using default color.\n{err}");
         Color::default()
     }))
 }
 
+/// Named colors supported by shields.io
+const NAMED_COLORS: [&str; 17] = [
+    "brightgreen",
+    "green",
+    "yellowgreen",
+    "yellow",
+    "orange",
+    "red",
+    "blue",
+    "lightgrey",
+    "lightgray",
+    "grey",
+    "gray",
+    "blueviolet",
+    "success",
+    "important",
+    "critical",
+    "informational",
+    "inactive",
+];
+
 impl FromStr for Color {
     type Err = &'static str;
 
@@ -122,14 +303,21 @@ impl FromStr for Color {
             return Ok(Color(None));
         }
 
+        if let Some(name) = NAMED_COLORS
+            .iter()
+            .find(|name| name.eq_ignore_ascii_case(s))
+        {
+            return Ok(Color(Some(name.to_string())));
+        }
+
         let hex = s.trim_start_matches('#');
 
         if hex.len() != 6 {
-            return Err("Color must be 6 hex digits");
+            return Err("Color must be 6 hex digits or a shields.io named color");
         }
 
         if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
-            return Err("Color must contain only hex digits");
+            return Err("Color must contain only hex digits or be a shields.io named color");
         }
 
         Ok(Color(Some(format!("#{hex}"))))
@@ -143,6 +331,13 @@ pub enum DownloadCount {
     Total,
     #[value(alias = "Unique")]
     Unique,
+    #[value(aliases = ["Endorsements", "endorsement", "Endorsement"])]
+    Endorsements,
+    #[value(alias = "Version")]
+    Version,
+    /// Total and unique downloads on one badge, e.g. `12.3M / 4.5M unique`
+    #[value(aliases = ["Both", "combined", "Combined"])]
+    Both,
 }
 
 impl Display for DownloadCount {
@@ -153,6 +348,9 @@ impl Display for DownloadCount {
             match self {
                 DownloadCount::Total => "Total downloads",
                 DownloadCount::Unique => "Unique downloads",
+                DownloadCount::Endorsements => "Endorsements",
+                DownloadCount::Version => "Mod version",
+                DownloadCount::Both => "Total and unique downloads",
             }
         )
     }
@@ -163,15 +361,87 @@ impl DownloadCount {
         match self {
             DownloadCount::Total => "mod_downloads",
             DownloadCount::Unique => "mod_unique_downloads",
+            DownloadCount::Endorsements => "endorsements",
+            DownloadCount::Version => "version",
+            DownloadCount::Both => "downloads_combined",
+        }
+    }
+
+    /// Returns `false` for metrics that can not be summed into the totals entry
+    pub fn has_total(&self) -> bool {
+        !matches!(self, DownloadCount::Version)
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Clone, Copy, Debug, ValueEnum)]
+pub enum SortBy {
+    #[default]
+    #[value(alias = "None")]
+    None,
+    #[value(alias = "Name")]
+    Name,
+    #[value(alias = "Downloads")]
+    Downloads,
+    #[value(alias = "Unique")]
+    Unique,
+}
+
+impl Display for SortBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                SortBy::None => "none",
+                SortBy::Name => "name",
+                SortBy::Downloads => "downloads",
+                SortBy::Unique => "unique",
+            }
+        )
+    }
+}
+
+impl SortBy {
+    /// Download counts are sorted in descending order, names in ascending order
+    pub fn sort(&self, entries: &mut [(String, ModDetails)]) {
+        match self {
+            SortBy::None => (),
+            SortBy::Name => entries.sort_by(|(_, a), (_, b)| a.name.cmp(&b.name)),
+            SortBy::Downloads => entries.sort_by_key(|(_, entry)| Reverse(entry.mod_downloads)),
+            SortBy::Unique => entries.sort_by_key(|(_, entry)| Reverse(entry.mod_unique_downloads)),
         }
     }
 }
 
+#[derive(Deserialize, Serialize, Default, Clone, Copy, Debug, ValueEnum)]
+pub enum TotalPosition {
+    #[value(alias = "First")]
+    First,
+    #[default]
+    #[value(alias = "Last")]
+    Last,
+}
+
+impl Display for TotalPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                TotalPosition::First => "first",
+                TotalPosition::Last => "last",
+            }
+        )
+    }
+}
+
 #[derive(Deserialize, Serialize, Default, Clone, Copy, Debug, ValueEnum)]
 pub enum BadgeFormat {
     #[default]
     #[value(alias = "Markdown")]
     Markdown,
+    #[value(aliases = ["MarkdownTable", "markdownTable", "Markdown-Table", "markdown_table", "table"])]
+    MarkdownTable,
     #[value(alias = "Url")]
     Url,
     #[value(alias = "rSt")]
@@ -180,6 +450,13 @@ pub enum BadgeFormat {
     AsciiDoc,
     #[value(aliases = ["HTML", "Html"])]
     Html,
+    #[value(aliases = ["Org", "org-mode", "OrgMode", "orgmode"])]
+    Org,
+    #[value(alias = "Textile")]
+    Textile,
+    /// Object of badge urls keyed by mod name, intended for user made templates
+    #[value(aliases = ["JSON", "Json"])]
+    Json,
 }
 
 impl Display for BadgeFormat {
@@ -189,10 +466,14 @@ impl Display for BadgeFormat {
             "{}",
             match self {
                 BadgeFormat::Markdown => "markdown",
+                BadgeFormat::MarkdownTable => "markdown-table",
                 BadgeFormat::AsciiDoc => "asciiDoc",
                 BadgeFormat::Html => "html",
                 BadgeFormat::Rst => "rst",
                 BadgeFormat::Url => "url",
+                BadgeFormat::Org => "org",
+                BadgeFormat::Textile => "textile",
+                BadgeFormat::Json => "json",
             }
         )
     }
@@ -229,6 +510,8 @@ pub struct EncodedFields<'a> {
     json_url: PercentEncode<'a>,
     label: PercentEncode<'a>,
     option_fields: String,
+    /// Option fields with the light mode label color in place of `label_color`
+    light_option_fields: Option<String>,
 }
 
 impl<'a> EncodedFields<'a> {
@@ -240,12 +523,110 @@ impl<'a> EncodedFields<'a> {
         EncodedFields {
             json_url: percent_encode(json_url.as_bytes(), ascii_set),
             label: percent_encode(badge_prefs.label.as_bytes(), ascii_set),
-            option_fields: badge_prefs.encode_optionals(ascii_set),
+            option_fields: badge_prefs.encode_optionals(
+                &badge_prefs.label_color,
+                &badge_prefs.color,
+                ascii_set,
+            ),
+            light_option_fields: badge_prefs.encode_light_optionals(&badge_prefs.color, ascii_set),
         }
     }
+
+    pub fn new_total(
+        json_url: &'a str,
+        badge_prefs: &'a BadgePreferences,
+        ascii_set: &'static AsciiSet,
+    ) -> Self {
+        let label = badge_prefs
+            .total_label
+            .as_ref()
+            .unwrap_or(&badge_prefs.label);
+        let color = if badge_prefs.total_color.is_none() {
+            &badge_prefs.color
+        } else {
+            &badge_prefs.total_color
+        };
+        EncodedFields {
+            json_url: percent_encode(json_url.as_bytes(), ascii_set),
+            label: percent_encode(label.as_bytes(), ascii_set),
+            option_fields: badge_prefs.encode_optionals(&badge_prefs.label_color, color, ascii_set),
+            light_option_fields: badge_prefs.encode_light_optionals(color, ascii_set),
+        }
+    }
+
+    pub fn with_label(&self, label: &'a str, ascii_set: &'static AsciiSet) -> Self {
+        EncodedFields {
+            json_url: self.json_url.clone(),
+            label: percent_encode(label.as_bytes(), ascii_set),
+            option_fields: self.option_fields.clone(),
+            light_option_fields: self.light_option_fields.clone(),
+        }
+    }
+
+    fn light_mode(&self) -> Option<Self> {
+        self.light_option_fields
+            .as_ref()
+            .map(|option_fields| EncodedFields {
+                json_url: self.json_url.clone(),
+                label: self.label.clone(),
+                option_fields: option_fields.clone(),
+                light_option_fields: None,
+            })
+    }
 }
 
+const IMAGE_ALT_TEXT: &str = "Nexus Downloads";
+
 impl BadgeFormat {
+    #[inline]
+    pub fn is_table(&self) -> bool {
+        matches!(self, BadgeFormat::MarkdownTable)
+    }
+
+    fn links_image(&self) -> bool {
+        matches!(
+            self,
+            BadgeFormat::Markdown
+                | BadgeFormat::MarkdownTable
+                | BadgeFormat::Org
+                | BadgeFormat::Textile
+        )
+    }
+
+    #[inline]
+    pub fn is_json(&self) -> bool {
+        matches!(self, BadgeFormat::Json)
+    }
+
+    /// Extension of the file badges are saved to
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            BadgeFormat::Markdown | BadgeFormat::MarkdownTable => "md",
+            BadgeFormat::Url => "txt",
+            BadgeFormat::Rst => "rst",
+            BadgeFormat::AsciiDoc => "adoc",
+            BadgeFormat::Html => "html",
+            BadgeFormat::Org => "org",
+            BadgeFormat::Textile => "textile",
+            BadgeFormat::Json => "json",
+        }
+    }
+
+    /// Formats that wrap the image in a link do not need shields.io to add the link
+    pub fn badge_url(
+        &self,
+        ascii_set: &'static AsciiSet,
+        encoded_data: &EncodedFields,
+        query: &str,
+        url: &str,
+    ) -> String {
+        if self.links_image() || url.is_empty() {
+            dynamic_badge_url(ascii_set, encoded_data, query)
+        } else {
+            dynamic_badge_url_with_link(ascii_set, encoded_data, query, url)
+        }
+    }
+
     pub fn write_badge(
         &self,
         f: &mut impl std::io::Write,
@@ -254,30 +635,74 @@ impl BadgeFormat {
         query: &str,
         url: &str,
     ) -> std::io::Result<()> {
-        const IMAGE_ALT_TEXT: &str = "Nexus Downloads";
+        let badge_url = self.badge_url(ascii_set, encoded_data, query, url);
 
-        let badge_url = if matches!(self, BadgeFormat::Markdown) || url.is_empty() {
-            dynamic_badge_url(ascii_set, encoded_data, query)
+        let lang = if self.is_table() {
+            BadgeFormat::Markdown
         } else {
-            dynamic_badge_url_with_link(ascii_set, encoded_data, query, url)
+            *self
         };
 
-        writeln!(f, "```{self}")?;
+        writeln!(f, "```{lang}")?;
         match self {
-            BadgeFormat::Markdown => {
-                if url.is_empty() {
-                    writeln!(f, "![{IMAGE_ALT_TEXT}]({badge_url})")?
-                } else {
-                    writeln!(f, "[![{IMAGE_ALT_TEXT}]({badge_url})]({url})")?
+            BadgeFormat::Markdown => match encoded_data.light_mode() {
+                Some(light_data) => {
+                    let light_url = dynamic_badge_url(ascii_set, &light_data, query);
+                    writeln!(
+                        f,
+                        "{}\n{}",
+                        markdown_image(&format!("{badge_url}#gh-dark-mode-only"), url),
+                        markdown_image(&format!("{light_url}#gh-light-mode-only"), url)
+                    )?
                 }
-            }
+                None => writeln!(f, "{}", markdown_image(&badge_url, url))?,
+            },
+            BadgeFormat::MarkdownTable => writeln!(f, "{}", markdown_image(&badge_url, url))?,
             BadgeFormat::AsciiDoc => writeln!(f, "image:{badge_url}[{IMAGE_ALT_TEXT}]")?,
             BadgeFormat::Html => writeln!(f, "<img alt=\"{IMAGE_ALT_TEXT}\" src=\"{badge_url}\">")?,
             BadgeFormat::Rst => writeln!(f, ".. image:: {badge_url}\n  :alt: {IMAGE_ALT_TEXT}")?,
             BadgeFormat::Url => writeln!(f, "{badge_url}")?,
+            BadgeFormat::Org if url.is_empty() => writeln!(f, "[[{badge_url}]]")?,
+            BadgeFormat::Org => writeln!(f, "[[{url}][{badge_url}]]")?,
+            BadgeFormat::Textile if url.is_empty() => {
+                writeln!(f, "!{badge_url}({IMAGE_ALT_TEXT})!")?
+            }
+            BadgeFormat::Textile => writeln!(f, "!{badge_url}({IMAGE_ALT_TEXT})!:{url}")?,
+            BadgeFormat::Json => writeln!(f, "{}", serde_json::Value::from(badge_url))?,
         }
         writeln!(f, "```")
     }
+
+    pub fn write_table_header(f: &mut impl std::io::Write) -> std::io::Result<()> {
+        writeln!(f, "```markdown")?;
+        writeln!(f, "| Mod | Badge |")?;
+        writeln!(f, "| --- | --- |")
+    }
+
+    pub fn write_table_row(
+        f: &mut impl std::io::Write,
+        ascii_set: &'static AsciiSet,
+        encoded_data: &EncodedFields,
+        query: &str,
+        name: &str,
+        url: &str,
+    ) -> std::io::Result<()> {
+        let badge_url = dynamic_badge_url(ascii_set, encoded_data, query);
+        writeln!(
+            f,
+            "| {} | {} |",
+            name.replace('|', "\\|"),
+            markdown_image(&badge_url, url)
+        )
+    }
+}
+
+fn markdown_image(badge_url: &str, url: &str) -> String {
+    if url.is_empty() {
+        format!("![{IMAGE_ALT_TEXT}]({badge_url})")
+    } else {
+        format!("[![{IMAGE_ALT_TEXT}]({badge_url})]({url})")
+    }
 }
 
 #[derive(Deserialize, Serialize, Default, Clone, Copy, Debug, ValueEnum)]
@@ -310,3 +735,163 @@ impl Display for BadgeStyle {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::URL_ENCODE_SET;
+
+    const JSON_URL: &str = "https://example.com/data.json";
+    const QUERY: &str = "$.mods.1.mod_downloads";
+    const MOD_URL: &str = "https://www.nexusmods.com/skyrim/mods/1";
+    const ENCODED_MOD_URL: &str = "https%3A%2F%2Fwww.nexusmods.com%2Fskyrim%2Fmods%2F1";
+    const BADGE_URL: &str = "https://img.shields.io/badge/dynamic/json\
+        ?url=https%3A%2F%2Fexample.com%2Fdata.json&query=%24.mods.1.mod_downloads\
+        &label=Nexus%20Downloads&cacheSeconds=3600";
+
+    fn render(format: BadgeFormat, badge_prefs: &BadgePreferences, url: &str) -> String {
+        let fields = EncodedFields::new(JSON_URL, badge_prefs, URL_ENCODE_SET);
+        let mut out = Vec::new();
+        format
+            .write_badge(&mut out, URL_ENCODE_SET, &fields, QUERY, url)
+            .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn every_format() {
+        let badge_prefs = BadgePreferences::default();
+        let linked = format!("{BADGE_URL}&link={ENCODED_MOD_URL}");
+        let cases = [
+            (
+                BadgeFormat::Markdown,
+                format!("```markdown\n[![Nexus Downloads]({BADGE_URL})]({MOD_URL})\n```\n"),
+            ),
+            (
+                BadgeFormat::MarkdownTable,
+                format!("```markdown\n[![Nexus Downloads]({BADGE_URL})]({MOD_URL})\n```\n"),
+            ),
+            (BadgeFormat::Url, format!("```url\n{linked}\n```\n")),
+            (
+                BadgeFormat::Rst,
+                format!("```rst\n.. image:: {linked}\n  :alt: Nexus Downloads\n```\n"),
+            ),
+            (
+                BadgeFormat::AsciiDoc,
+                format!("```asciiDoc\nimage:{linked}[Nexus Downloads]\n```\n"),
+            ),
+            (
+                BadgeFormat::Html,
+                format!("```html\n<img alt=\"Nexus Downloads\" src=\"{linked}\">\n```\n"),
+            ),
+            (
+                BadgeFormat::Org,
+                format!("```org\n[[{MOD_URL}][{BADGE_URL}]]\n```\n"),
+            ),
+            (
+                BadgeFormat::Textile,
+                format!("```textile\n!{BADGE_URL}(Nexus Downloads)!:{MOD_URL}\n```\n"),
+            ),
+            (BadgeFormat::Json, format!("```json\n\"{linked}\"\n```\n")),
+        ];
+
+        for (format, expected) in cases {
+            assert_eq!(render(format, &badge_prefs, MOD_URL), expected, "{format}");
+        }
+    }
+
+    #[test]
+    fn without_link() {
+        let badge_prefs = BadgePreferences::default();
+        assert_eq!(
+            render(BadgeFormat::Markdown, &badge_prefs, ""),
+            format!("```markdown\n![Nexus Downloads]({BADGE_URL})\n```\n")
+        );
+        assert_eq!(
+            render(BadgeFormat::Url, &badge_prefs, ""),
+            format!("```url\n{BADGE_URL}\n```\n")
+        );
+        assert_eq!(
+            render(BadgeFormat::Org, &badge_prefs, ""),
+            format!("```org\n[[{BADGE_URL}]]\n```\n")
+        );
+        assert_eq!(
+            render(BadgeFormat::Textile, &badge_prefs, ""),
+            format!("```textile\n!{BADGE_URL}(Nexus Downloads)!\n```\n")
+        );
+    }
+
+    #[test]
+    fn unset_cache_seconds_persists() {
+        let badge_prefs = BadgePreferences {
+            cache_seconds: None,
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&badge_prefs).unwrap();
+        let read = serde_json::from_str::<BadgePreferences>(&json).unwrap();
+        assert_eq!(read.cache_seconds, None);
+
+        let read = serde_json::from_str::<BadgePreferences>("{}").unwrap();
+        assert_eq!(read.cache_seconds, Some(DEFAULT_CACHE_SECONDS));
+    }
+
+    #[test]
+    fn url_format_writes_txt() {
+        assert_eq!(BadgeFormat::Url.file_extension(), "txt");
+        assert_eq!(BadgeFormat::MarkdownTable.file_extension(), "md");
+    }
+
+    #[test]
+    fn old_label_color_name_still_reads() {
+        let old: BadgePreferences = serde_json::from_str(
+            r##"{"label_color":"#23282e","label_color_light":"#ffffff","cache_seconds":60}"##,
+        )
+        .unwrap();
+        let new: BadgePreferences = serde_json::from_str(
+            r##"{"label_color_dark":"#23282e","label_color_light":"#ffffff","cache_seconds":60}"##,
+        )
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(&old).unwrap(),
+            serde_json::to_value(&new).unwrap()
+        );
+        assert_eq!(old.label_color.rgb(), new.label_color.rgb());
+    }
+
+    #[test]
+    fn light_mode_pair() {
+        let badge_prefs = BadgePreferences {
+            label_color: Color::from_str("23282e").unwrap(),
+            label_color_light: Color::from_str("#ffffff").unwrap(),
+            cache_seconds: None,
+            ..Default::default()
+        };
+        let base = "https://img.shields.io/badge/dynamic/json\
+            ?url=https%3A%2F%2Fexample.com%2Fdata.json&query=%24.mods.1.mod_downloads\
+            &label=Nexus%20Downloads";
+        assert_eq!(
+            render(BadgeFormat::Markdown, &badge_prefs, MOD_URL),
+            format!(
+                "```markdown\n\
+                [![Nexus Downloads]({base}&labelColor=%2323282e#gh-dark-mode-only)]({MOD_URL})\n\
+                [![Nexus Downloads]({base}&labelColor=%23ffffff#gh-light-mode-only)]({MOD_URL})\n\
+                ```\n"
+            )
+        );
+    }
+
+    #[test]
+    fn label_is_percent_encoded() {
+        let badge_prefs = BadgePreferences {
+            label: String::from("Mods - A_B & C"),
+            ..Default::default()
+        };
+        let fields = EncodedFields::new(JSON_URL, &badge_prefs, URL_ENCODE_SET);
+        assert_eq!(
+            BadgeFormat::Url.badge_url(URL_ENCODE_SET, &fields, QUERY, ""),
+            "https://img.shields.io/badge/dynamic/json\
+            ?url=https%3A%2F%2Fexample.com%2Fdata.json&query=%24.mods.1.mod_downloads\
+            &label=Mods%20-%20A_B%20%26%20C&cacheSeconds=3600"
+        );
+    }
+}
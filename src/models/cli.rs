@@ -1,6 +1,20 @@
-use crate::models::badge_options::{BadgeFormat, BadgeStyle, Color, DownloadCount};
-use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+use crate::{
+    models::badge_options::{
+        BadgeFormat, BadgeStyle, Color, DownloadCount, SortBy, TotalPosition, MAX_LOGO_WIDTH,
+        MIN_CACHE_SECONDS,
+    },
+    parse_utc_date,
+    services::git::{is_gist_id, strip_gist_url},
+    unix_timestamp, SECS_PER_DAY, TOTAL_KEY,
+};
+use clap::{builder::FalseyValueParser, ArgAction, Args, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use log::LevelFilter;
 use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, str::FromStr};
+
+/// Upper limit enforced on user set gist descriptions
+const MAX_GIST_DESC_LEN: usize = 256;
 
 #[derive(Parser, Debug)]
 pub struct Cli {
@@ -10,38 +24,296 @@ pub struct Cli {
 
     #[arg(long, action = ArgAction::SetTrue, hide = true)]
     pub remote: bool,
+
+    /// Exit with code 75 on remote runs that left the gist unchanged, only set by workflows that
+    /// accept the code
+    #[arg(long, action = ArgAction::SetTrue, hide = true, requires = "remote")]
+    pub exit_unchanged: bool,
+
+    /// Seconds to wait on a network request before giving up
+    #[arg(
+        long,
+        global = true,
+        env = "NEXUS_BADGES_TIMEOUT",
+        default_value_t = 30
+    )]
+    pub timeout: u64,
+
+    /// Url of a proxy to send every request through, overrides the proxy set by the 'HTTPS_PROXY',
+    /// {n}  'HTTP_PROXY', and 'ALL_PROXY' environment variables. Hosts listed in 'NO_PROXY' are
+    /// {n}  still reached directly
+    #[arg(
+        long,
+        global = true,
+        value_name = "URL",
+        env = "NEXUS_BADGES_PROXY",
+        value_parser = parse_proxy
+    )]
+    pub proxy: Option<reqwest::Proxy>,
+
+    /// PEM or DER encoded root certificate to trust in addition to the system certificates, e.g.
+    /// {n}  the certificate of a TLS intercepting proxy
+    #[arg(long, global = true, value_name = "PATH", env = "NEXUS_BADGES_CA_CERT")]
+    pub ca_cert: Option<PathBuf>,
+
+    /// Disable TLS certificate verification. Insecure, only use this for debugging
+    #[arg(long, global = true)]
+    pub danger_insecure: bool,
+
+    /// When to use colored output, 'auto' uses color in a terminal unless the 'NO_COLOR'
+    /// {n}  environment variable is set
+    #[arg(long, value_name = "WHEN", value_enum, default_value_t, global = true)]
+    pub color_mode: ColorChoice,
+
+    /// Disable colored output, same as '--color-mode never' and takes priority over it
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Print command results as machine-readable JSON
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Increase logging verbosity [-v: debug, -vv: trace]
+    #[arg(short, long, global = true, action = ArgAction::Count, conflicts_with = "quiet")]
+    pub verbose: u8,
+
+    /// Only print warnings and errors
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// Directory to read and write all input and output files in
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        env = "NEXUS_BADGES_CONFIG_DIR"
+    )]
+    pub config_dir: Option<PathBuf>,
+
+    /// Path of the input file, takes priority over '--config-dir'
+    #[arg(long, global = true, value_name = "PATH")]
+    pub input: Option<PathBuf>,
+
+    /// Path of the download count output file, takes priority over '--config-dir'
+    #[arg(long, global = true, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Path of the generated badges file, takes priority over '--config-dir'
+    #[arg(long, global = true, value_name = "PATH")]
+    pub badges: Option<PathBuf>,
+
+    /// Skip checking for a new version of Nexus Badges
+    #[arg(
+        long,
+        global = true,
+        env = "NEXUS_BADGES_NO_UPDATE_CHECK",
+        value_parser = FalseyValueParser::new()
+    )]
+    pub no_version_check: bool,
+
+    /// Continue with the remaining mods when download counts for a mod can not be retrieved,
+    /// {n}  mods that fail are left out of the output until they succeed again
+    #[arg(
+        long,
+        global = true,
+        env = "NEXUS_BADGES_SKIP_ERRORS",
+        value_parser = FalseyValueParser::new()
+    )]
+    pub skip_errors: bool,
+
+    /// Preview updated download counts and badges without modifying the remote gist or local files
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Update the remote gist and output file without writing the badges file
+    #[arg(long, conflicts_with = "dry_run")]
+    pub no_badges: bool,
+
+    /// Write the badges file from the counts in the existing output file, no network requests are
+    /// {n}  made and the remote gist is not modified
+    #[arg(long, conflicts_with_all = ["dry_run", "no_badges"])]
+    pub badges_only: bool,
+}
+
+impl Cli {
+    /// Status updates are silenced in JSON mode unless verbosity was explicitly raised
+    pub fn log_level(&self) -> LevelFilter {
+        match self.verbose {
+            0 if self.quiet || self.json => LevelFilter::Warn,
+            0 => LevelFilter::Info,
+            1 => LevelFilter::Debug,
+            _ => LevelFilter::Trace,
+        }
+    }
+
+    #[inline]
+    pub fn color_choice(&self) -> ColorChoice {
+        if self.no_color {
+            ColorChoice::Never
+        } else {
+            self.color_mode
+        }
+    }
+
+    #[inline]
+    pub fn output_mode(&self) -> OutputMode {
+        if self.json {
+            OutputMode::Json
+        } else {
+            OutputMode::Human
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputMode {
+    #[default]
+    Human,
+    Json,
+}
+
+impl OutputMode {
+    #[inline]
+    pub fn is_json(self) -> bool {
+        matches!(self, OutputMode::Json)
+    }
 }
 
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Add/Register a Nexus mod to track the download count of
     #[command(alias = "Add")]
-    Add(Mod),
+    Add(AddArgs),
 
     /// Remove and stop tracking the download count of a registered mod
     #[command(alias = "Remove")]
     Remove(Mod),
 
+    /// Register every mod an author has uploaded for a game, run again to pick up mods the
+    /// {n}  author has published since
+    #[command(aliases = ["AddAuthor", "add_author", "addAuthor"])]
+    AddAuthor {
+        /// The name of the game the author's mods are made for
+        #[arg(short, long, alias = "game")]
+        domain: String,
+
+        /// Nexus username of the mod author
+        #[arg(short, long)]
+        author: String,
+    },
+
+    /// List all registered Nexus mods
+    #[command(aliases = ["List", "ls"])]
+    List,
+
+    /// Export registered Nexus mods to a portable json file, credentials are not included
+    #[command(alias = "Export")]
+    Export {
+        /// File to write the registered mods to
+        out: PathBuf,
+    },
+
+    /// Retrieve current download counts and write them as json or csv, the remote gist and
+    /// {n}  local files are not modified
+    #[command(alias = "Dump")]
+    Dump {
+        /// File to write the download counts to [Default: stdout]
+        out: Option<PathBuf>,
+
+        /// Format to write the download counts as
+        #[arg(long = "as", value_name = "FORMAT", value_enum, default_value_t)]
+        format: DumpFormat,
+    },
+
+    /// Print the download growth of each tracked mod over a window of the stored history
+    #[command(alias = "Stats")]
+    Stats {
+        /// Start of the window, as a number of days ago (e.g. '7d', '30d') or a UTC date
+        /// {n}  formatted as YYYY-MM-DD [Default: oldest stored snapshot]
+        #[arg(long, value_parser = parse_since)]
+        since: Option<u64>,
+    },
+
+    /// Re-fetch the names of tracked mods and rewrite the local output and badge files, mods
+    /// {n}  published since by authors registered with 'add-author' are also registered
+    #[command(alias = "Refresh")]
+    Refresh {
+        /// Also update the remote gist with the refreshed details
+        #[arg(long)]
+        push: bool,
+    },
+
+    /// Write the badges file from the counts saved in the output file, e.g. after changing badge
+    /// {n}  preferences. The remote is only contacted if the output file lacks the gist url
+    #[command(alias = "Badges")]
+    Badges,
+
+    /// Import Nexus mods from a file created by 'export'
+    #[command(alias = "Import")]
+    Import {
+        /// File to read mods from
+        path: PathBuf,
+
+        /// Add imported mods to the registered mods instead of replacing them
+        #[arg(long)]
+        merge: bool,
+    },
+
+    /// Swap 'input.json' with the backup of its previous contents, run again to undo
+    #[command(alias = "Restore")]
+    Restore,
+
     /// Configure necessary credentials for NexusMod and Git API calls
     /// {n}  and set badge style preferences
     #[command(aliases = ["Set", "set"])]
-    SetArg(SetArgs),
+    SetArg(Box<SetArgs>),
+
+    /// Interactively configure credentials and initialize the remote gist and GitHub actions
+    #[command(alias = "Setup")]
+    Setup,
+
+    /// Check that the stored Nexus key, git token, and gist are all usable
+    #[command(alias = "Validate")]
+    Validate,
 
     /// Initalize private gist to be used as a json endpoint for badge download counters
     #[command(alias = "Init")]
     Init,
 
+    /// Permanently delete the remote gist and clear the stored gist_id
+    #[command(aliases = ["DeleteGist", "delete_gist", "Delete-Gist", "deleteGist"])]
+    DeleteGist {
+        /// Skip confirmation before deleting the remote gist
+        #[arg(short, long, alias = "force")]
+        yes: bool,
+    },
+
     /// Initalize GitHub actions to update the remote gist once daily
     #[command(aliases = ["InitActions", "init_actions", "Init-Actions", "initActions"])]
     InitActions,
 
-    /// Enable/Disable the GitHub action automation workflow
+    /// Enable/Disable the GitHub action automation workflow, or print its current status
     #[command(alias = "Automation")]
     Automation {
         #[arg(value_enum)]
         state: Workflow,
     },
 
+    /// Print a shell completion script to stdout
+    #[command(alias = "Completions")]
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+
+    /// Print a roff man page to stdout, or write one page per command to a directory
+    #[command(hide = true)]
+    Manpage {
+        /// Directory to write man pages for all commands to
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+
     /// Display current version and check for updates
     #[command(alias = "Version")]
     Version,
@@ -53,13 +325,29 @@ pub enum Commands {
         #[arg(long)]
         old: Option<String>,
 
+        /// Delete every cache whose key starts with this prefix, other than the new key
+        #[arg(long, conflicts_with = "old")]
+        prefix: Option<String>,
+
         /// Cache repository variable to be updated
         #[arg(long)]
         new: String,
     },
+
+    /// Set one or more repository variables, e.g. after forking [Not supported on local]
+    #[command(hide = true)]
+    SetRepoVar {
+        /// Variables to set, formatted as NAME=VALUE
+        #[arg(required = true, value_parser = parse_repo_var)]
+        vars: Vec<(String, String)>,
+
+        /// Cache Key to be deleted
+        #[arg(long)]
+        delete_cache: Option<String>,
+    },
 }
 
-#[derive(Args, Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+#[derive(Args, Deserialize, Serialize, Debug, Clone)]
 pub struct Mod {
     /// The name of the game the mod is made for
     #[arg(short, long, alias = "game")]
@@ -68,6 +356,104 @@ pub struct Mod {
     /// The ID of the mod
     #[arg(short, long, alias = "id")]
     pub mod_id: usize,
+
+    /// Specify a label to use on this mod's badge in place of the global label
+    /// {n}  [Tip: use quotes to include spaces]
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+
+    /// Key to store this mod's counts under in place of its Nexus uid
+    /// {n}  [Tip: allows tracking the same mod more than once]
+    #[arg(long, value_parser = parse_output_key)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+}
+
+impl FromStr for Mod {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (domain, mod_id) = s
+            .trim()
+            .split_once(':')
+            .ok_or_else(|| format!("expected `domain:mod_id`, found: {s}"))?;
+        Ok(Mod {
+            domain: domain.trim().to_string(),
+            mod_id: mod_id
+                .trim()
+                .parse()
+                .map_err(|err| format!("invalid mod_id in: {s}, {err}"))?,
+            label: None,
+            key: None,
+        })
+    }
+}
+
+/// Nexus game domains are case-insensitive, so `SkyrimSpecialEdition` and `skyrimspecialedition`
+/// refer to the same mod
+impl PartialEq for Mod {
+    fn eq(&self, other: &Self) -> bool {
+        self.domain.eq_ignore_ascii_case(&other.domain)
+            && self.mod_id == other.mod_id
+            && self.key == other.key
+    }
+}
+
+impl Eq for Mod {}
+
+#[derive(Args, Debug)]
+pub struct AddArgs {
+    /// The name of the game the mod is made for
+    #[arg(
+        short,
+        long,
+        alias = "game",
+        requires = "mod_id",
+        required_unless_present_any = ["batch", "from_file"]
+    )]
+    pub domain: Option<String>,
+
+    /// The ID of the mod
+    #[arg(short, long, alias = "id", requires = "domain")]
+    pub mod_id: Option<usize>,
+
+    /// Specify a label to use on this mod's badge in place of the global label
+    /// {n}  [Tip: use quotes to include spaces]
+    #[arg(long, requires = "domain")]
+    pub label: Option<String>,
+
+    /// Key to store this mod's counts under in place of its Nexus uid
+    /// {n}  [Tip: allows tracking the same mod more than once]
+    #[arg(long, requires = "domain", value_parser = parse_output_key)]
+    pub key: Option<String>,
+
+    /// Comma separated list of `domain:mod_id` pairs to register
+    /// {n}  [Example: skyrimspecialedition:266,fallout4:1235]
+    #[arg(long, value_delimiter = ',', conflicts_with = "domain")]
+    pub batch: Vec<Mod>,
+
+    /// File containing `domain:mod_id` pairs to register, one per line
+    #[arg(long, value_name = "PATH", conflicts_with = "domain")]
+    pub from_file: Option<PathBuf>,
+}
+
+impl AddArgs {
+    /// Returns `Err(self)` when a batch of mods was given
+    pub fn into_mod(self) -> Result<Mod, Box<Self>> {
+        match (self.domain, self.mod_id) {
+            (Some(domain), Some(mod_id)) => Ok(Mod {
+                domain,
+                mod_id,
+                label: self.label,
+                key: self.key,
+            }),
+            (domain, mod_id) => Err(Box::new(AddArgs {
+                domain,
+                mod_id,
+                ..self
+            })),
+        }
+    }
 }
 
 #[derive(Args, Debug, Default)]
@@ -79,29 +465,86 @@ pub struct SetArgs {
     #[arg(long, alias = "git-token")]
     pub git: Option<String>,
 
+    /// Read the Github token from a file, use '-' to enter it from stdin without echo
+    #[arg(long, value_name = "PATH", conflicts_with = "git")]
+    pub git_file: Option<PathBuf>,
+
     /// Nexus private api key
     #[arg(long, alias = "nexus-key")]
     pub nexus: Option<String>,
 
+    /// Read the Nexus api key from a file, use '-' to enter it from stdin without echo
+    #[arg(long, value_name = "PATH", conflicts_with = "nexus")]
+    pub nexus_file: Option<PathBuf>,
+
     /// Identifier of the target remote Gist
     /// {n}  [This value is automatically set by the `init` command]
-    #[arg(long, alias = "gist-id")]
+    /// {n}  [Tip: a gist url copied from the browser is accepted]
+    #[arg(long, alias = "gist-id", value_parser = parse_gist_id)]
     pub gist: Option<String>,
 
+    /// Name of the json file within the target remote Gist [Default: nexus_badges.json]
+    /// {n}  [Tip: to use the default name set as default]
+    #[arg(long)]
+    pub gist_file: Option<String>,
+
+    /// Create the remote Gist as public [Default: false]
+    /// {n}  [Run `init` again for a change to apply to an existing Gist]
+    #[arg(long)]
+    pub public_gist: Option<bool>,
+
+    /// Description of the remote Gist [Default: Gist to be used as a json endpoint for badge
+    /// download counters]
+    /// {n}  [Tip: to use the default description set as default]
+    #[arg(long, alias = "gist-description", value_parser = parse_gist_description)]
+    pub gist_desc: Option<String>,
+
     /// Your GitHub user name [Required for GitHub actions setup]
     #[arg(long)]
     pub owner: Option<String>,
 
     /// Name of repository containing 'automation.yml' without the .git extension
-    /// {n}  [Required for GitHub actions setup]
+    /// {n}  [Required for GitHub actions setup] [Tip: a full repository url also sets '--owner']
     #[arg(long)]
     pub repo: Option<String>,
 
+    /// Base url of the GitHub api [Default: https://api.github.com]
+    /// {n}  [Tip: GitHub Enterprise Server urls look like https://HOSTNAME/api/v3]
+    #[arg(long, value_parser = parse_base_url)]
+    pub git_base_url: Option<String>,
+
+    /// Service that hosts the json endpoint the badges read from [Default: github]
+    /// {n}  [Run `init` again for a change to apply]
+    #[arg(long)]
+    pub backend: Option<Backend>,
+
+    /// GitLab personal access token with the api scope [Required for the gitlab backend]
+    #[arg(long)]
+    pub gitlab_token: Option<String>,
+
+    /// Base url of the GitLab api [Default: https://gitlab.com/api/v4]
+    /// {n}  [Tip: self-managed GitLab urls look like https://HOSTNAME/api/v4]
+    #[arg(long, value_parser = parse_base_url)]
+    pub gitlab_base_url: Option<String>,
+
+    /// Url the counts json is PUT to and read from [Required for the http backend]
+    /// {n}  [Note: plain http is only accepted for localhost]
+    #[arg(long, value_parser = parse_endpoint_url)]
+    pub endpoint_url: Option<String>,
+
+    /// Bearer token sent with every request to '--endpoint-url' [Required for the http backend]
+    #[arg(long)]
+    pub endpoint_token: Option<String>,
+
+    /// Maximum number of download count snapshots kept in 'history.json' [Default: 365]
+    #[arg(long)]
+    pub history_limit: Option<usize>,
+
     /// Specify a style to be added to badges [Default: flat]{n}  
     #[arg(long)]
     pub style: Option<BadgeStyle>,
 
-    /// Specify download count to use [Default: total]
+    /// Specify download count or metric to use [Default: total]
     #[arg(long)]
     pub count: Option<DownloadCount>,
 
@@ -110,35 +553,293 @@ pub struct SetArgs {
     #[arg(long)]
     pub label: Option<String>,
 
-    /// Specify a hex color for label side of the badge
-    /// {n}  [Tip: input colors as '#23282e' or 23282e]
-    #[arg(long)]
+    /// Specify a hex or named color for label side of the badge, only used in dark mode
+    /// {n}  when '--label-color-light' is set
+    /// {n}  [Tip: input colors as '#23282e', 23282e, or brightgreen]
+    #[arg(long = "label-color-dark", alias = "label-color")]
     pub label_color: Option<Color>,
 
-    /// Specify a hex color for counter side of the badge
+    /// Specify a hex or named color for label side of the badge when viewed in light mode
+    /// {n}  [Markdown format only] [Tip: set as default to only output a single badge image]
+    #[arg(long)]
+    pub label_color_light: Option<Color>,
+
+    /// Specify a hex or named color for counter side of the badge
     /// {n} [Tip: to remove a color set as default]
     #[arg(long)]
     pub color: Option<Color>,
 
+    /// Specify a simple-icons slug to display as a logo on badges
+    /// {n}  [Tip: to remove a logo set as none]
+    #[arg(long)]
+    pub logo: Option<String>,
+
+    /// Embed a local image as the logo on badges, for logos not available on simple-icons
+    /// {n}  [Supported: png, jpg, gif, webp, svg]
+    #[arg(long, value_name = "PATH", conflicts_with = "logo")]
+    pub logo_file: Option<PathBuf>,
+
+    /// Specify a hex or named color for the logo on the badge [Requires: logo]
+    /// {n}  [Tip: to remove a color set as default]
+    #[arg(long)]
+    pub logo_color: Option<Color>,
+
+    /// Specify the horizontal space reserved for the logo, fixes cropped wide logos
+    /// {n}  [Requires: logo] [Range: 1-100] [Tip: to remove a width set as default]
+    #[arg(long, value_parser = parse_logo_width)]
+    pub logo_width: Option<u32>,
+
+    /// Specify how long shields.io may cache a rendered badge in seconds [Default: 3600]
+    /// {n}  [Minimum: 300] [Tip: set as 0 to leave caching to shields.io]
+    #[arg(long, value_parser = parse_cache_seconds)]
+    pub cache_seconds: Option<u32>,
+
+    /// Specify label to use on the badge that totals all tracked mods
+    /// {n}  [Default: same as label]
+    #[arg(long)]
+    pub total_label: Option<String>,
+
+    /// Specify a hex or named color for counter side of the badge that totals all tracked mods
+    /// {n}  [Tip: to remove a color set as default]
+    #[arg(long)]
+    pub total_color: Option<Color>,
+
+    /// Specify the order badges are listed in [Default: none]{n}  
+    #[arg(long)]
+    pub sort: Option<SortBy>,
+
+    /// Specify if the totals badge is listed first or last [Default: last]{n}  
+    #[arg(long)]
+    pub total_position: Option<TotalPosition>,
+
+    /// Include a badge showing when download counts were last updated [Default: false]
+    #[arg(long)]
+    pub updated_badge: Option<bool>,
+
+    /// Make each badge a link to the mod's Nexus page [Default]
+    #[arg(long, overrides_with = "no_link")]
+    pub link: bool,
+
+    /// Write badges without a link to the mod's Nexus page
+    #[arg(long, overrides_with = "link")]
+    pub no_link: bool,
+
     /// Specify the output format of the generated badges [Default: Markdown]{n}  
     #[arg(long)]
     pub format: Option<BadgeFormat>,
 
+    /// Skip confirmation before replacing a previously stored gist_id
+    #[arg(short, long, alias = "force")]
+    pub yes: bool,
+
     #[clap(skip)]
     pub modified: ModFlags,
 }
 
+fn parse_base_url(s: &str) -> Result<String, String> {
+    if s.eq_ignore_ascii_case("default") {
+        return Ok(String::new());
+    }
+    let url = reqwest::Url::parse(s).map_err(|err| err.to_string())?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(String::from("url must use the http or https scheme"));
+    }
+    Ok(s.trim_end_matches('/').to_string())
+}
+
+fn parse_endpoint_url(s: &str) -> Result<String, String> {
+    let url = reqwest::Url::parse(s).map_err(|err| err.to_string())?;
+    match url.scheme() {
+        "https" => (),
+        "http" if matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]")) => (),
+        _ => return Err(String::from("url must use the https scheme")),
+    }
+    Ok(url.to_string())
+}
+
+/// Returns the unix timestamp that `s` refers to
+fn parse_since(s: &str) -> Result<u64, String> {
+    if let Some(days) = s.strip_suffix(['d', 'D']) {
+        let days = days
+            .parse::<u64>()
+            .map_err(|err| format!("invalid number of days, {err}"))?;
+        return Ok(unix_timestamp().saturating_sub(days.saturating_mul(SECS_PER_DAY)));
+    }
+    parse_utc_date(s).ok_or_else(|| {
+        String::from("expected a number of days (e.g. '7d') or a date formatted as YYYY-MM-DD")
+    })
+}
+
+fn parse_output_key(s: &str) -> Result<String, String> {
+    let key = s.trim();
+    if key.is_empty() {
+        return Err(String::from("key can not be empty"));
+    }
+    if key == TOTAL_KEY {
+        return Err(format!(
+            "'{TOTAL_KEY}' is reserved for the sum of all tracked mods"
+        ));
+    }
+    if !key
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(String::from(
+            "key may only contain ascii letters, numbers, '_', and '-'",
+        ));
+    }
+    Ok(key.to_string())
+}
+
+fn parse_gist_id(s: &str) -> Result<String, String> {
+    let id = strip_gist_url(s.trim());
+    if !is_gist_id(id) {
+        return Err(String::from(
+            "expected a gist id made of hex characters, or the url of a gist",
+        ));
+    }
+    Ok(id.to_string())
+}
+
+fn parse_proxy(s: &str) -> Result<reqwest::Proxy, String> {
+    reqwest::Proxy::all(s.trim())
+        .map(|proxy| proxy.no_proxy(reqwest::NoProxy::from_env()))
+        .map_err(|_| String::from("expected the url of a proxy e.g. 'http://127.0.0.1:3128'"))
+}
+
+fn parse_gist_description(s: &str) -> Result<String, String> {
+    let desc = s.trim();
+    if desc.eq_ignore_ascii_case("default") {
+        return Ok(String::new());
+    }
+    if desc.chars().count() > MAX_GIST_DESC_LEN {
+        return Err(format!(
+            "description can not be longer than {MAX_GIST_DESC_LEN} characters"
+        ));
+    }
+    Ok(desc.to_string())
+}
+
+fn parse_cache_seconds(s: &str) -> Result<u32, String> {
+    let seconds = s.parse::<u32>().map_err(|err| err.to_string())?;
+    if seconds != 0 && seconds < MIN_CACHE_SECONDS {
+        return Err(format!(
+            "shields.io requires a minimum of {MIN_CACHE_SECONDS} seconds"
+        ));
+    }
+    Ok(seconds)
+}
+
+/// GitHub requires variable names of only alphanumerics and underscores, that do not start with a
+/// number or the reserved `GITHUB_` prefix
+fn parse_repo_var(s: &str) -> Result<(String, String), String> {
+    let (name, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected `NAME=VALUE`, found: {s}"))?;
+    let name = name.trim();
+    if name.is_empty()
+        || name.starts_with(|c: char| c.is_ascii_digit())
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return Err(format!(
+            "invalid variable name: '{name}', only letters, numbers, and underscores are allowed \
+            and it can not start with a number"
+        ));
+    }
+    if name.to_ascii_uppercase().starts_with("GITHUB_") {
+        return Err(String::from("variable names can not start with 'GITHUB_'"));
+    }
+    Ok((name.to_string(), value.to_string()))
+}
+
+/// `0` is returned for 'default', which clears the stored width
+fn parse_logo_width(s: &str) -> Result<u32, String> {
+    if s.eq_ignore_ascii_case("default") {
+        return Ok(0);
+    }
+    let width = s.parse::<u32>().map_err(|err| err.to_string())?;
+    if !(1..=MAX_LOGO_WIDTH).contains(&width) {
+        return Err(format!("logo width must be between 1 and {MAX_LOGO_WIDTH}"));
+    }
+    Ok(width)
+}
+
 #[derive(Debug, Default)]
 pub struct ModFlags {
     pub git_token: bool,
     pub nexus_key: bool,
     pub gist_id: bool,
+    pub gist_file: bool,
+    pub backend: bool,
+    pub gitlab_token: bool,
+    pub gitlab_base_url: bool,
+    pub endpoint_url: bool,
+    pub endpoint_token: bool,
+    pub updated_badge: bool,
 }
 
 impl ModFlags {
     #[inline]
     pub fn any(&self) -> bool {
-        self.git_token || self.nexus_key || self.gist_id
+        self.git_token
+            || self.nexus_key
+            || self.gist_id
+            || self.gist_file
+            || self.backend
+            || self.gitlab_token
+            || self.gitlab_base_url
+            || self.endpoint_url
+            || self.endpoint_token
+            || self.updated_badge
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Default, Debug)]
+pub enum DumpFormat {
+    /// Spreadsheet friendly rows of name,uid,downloads,unique,url
+    #[default]
+    Csv,
+    /// Same contents as the remote gist
+    Json,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Default, Debug)]
+pub enum ColorChoice {
+    /// Color output written to a terminal, unless 'NO_COLOR' is set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Serialize, Deserialize, Default, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// GitHub gist
+    #[default]
+    #[value(aliases = ["GitHub", "Github", "gist"])]
+    Github,
+    /// Public GitLab snippet
+    #[value(aliases = ["GitLab", "Gitlab", "snippet"])]
+    Gitlab,
+    /// Any https endpoint that accepts an authenticated PUT
+    #[value(aliases = ["HTTP", "Http", "put"])]
+    Http,
+}
+
+impl Backend {
+    #[inline]
+    pub fn is_github(&self) -> bool {
+        matches!(self, Backend::Github)
+    }
+
+    /// Value stored in the `REMOTE_BACKEND` repository variable
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Backend::Github => "github",
+            Backend::Gitlab => "gitlab",
+            Backend::Http => "http",
+        }
     }
 }
 
@@ -148,4 +849,27 @@ pub enum Workflow {
     Enable,
     #[value(alias = "Disable")]
     Disable,
+    /// Print if the workflow is enabled and the outcome of its latest run
+    #[value(alias = "Status")]
+    Status,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_seconds_zero_unsets() {
+        assert_eq!(parse_cache_seconds("0"), Ok(0));
+        assert_eq!(parse_cache_seconds("300"), Ok(300));
+        assert!(parse_cache_seconds("299").is_err());
+    }
+
+    #[test]
+    fn mod_domain_ignores_case() {
+        let upper = Mod::from_str("SkyrimSpecialEdition:1").unwrap();
+        let lower = Mod::from_str("skyrimspecialedition:1").unwrap();
+        assert_eq!(upper, lower);
+        assert_ne!(upper, Mod::from_str("skyrimspecialedition:2").unwrap());
+    }
 }
@@ -1,16 +1,42 @@
+use crate::{
+    redact,
+    services::{gitlab::is_gitlab_endpoint, http::is_http_endpoint},
+};
+use reqwest::StatusCode;
 use std::{
     borrow::Cow,
     fmt::{Debug, Display},
     io,
+    time::Duration,
 };
 
 pub enum Error {
     Io(io::Error),
     SerdeJson(serde_json::Error),
     Reqwest(reqwest::Error),
-    BadResponse(String),
+    Timeout(reqwest::Error),
+    Connect(reqwest::Error),
+    BadResponse {
+        status: StatusCode,
+        endpoint: String,
+        body: String,
+        /// Service named in the error, inferred from `endpoint` when not known at the call site
+        service: Option<&'static str>,
+    },
+    InvalidResponse(String),
+    RateLimited {
+        retry_after: Duration,
+    },
+    NotFound(String),
+    /// The mod exists but is hidden, under moderation, or gated behind account settings
+    Restricted {
+        url: String,
+        reason: Option<String>,
+    },
     NotSetup(&'static str),
     Missing(&'static str),
+    /// Repository permissions a fine-grained GitHub token was refused for
+    MissingPermissions(Vec<&'static str>),
     Decode(base64::DecodeError),
     Encrypt(crypto_box::aead::Error),
     Env(std::env::VarError),
@@ -19,7 +45,13 @@ pub enum Error {
 
 impl From<reqwest::Error> for Error {
     fn from(value: reqwest::Error) -> Self {
-        Error::Reqwest(value)
+        if value.is_timeout() {
+            Error::Timeout(value)
+        } else if value.is_connect() {
+            Error::Connect(value)
+        } else {
+            Error::Reqwest(value)
+        }
     }
 }
 
@@ -60,13 +92,82 @@ impl From<tokio::task::JoinError> for Error {
 }
 
 impl Error {
+    /// Consumes an unexpected `response` to build a [`Error::BadResponse`]
+    pub(crate) async fn bad_response(response: reqwest::Response) -> Self {
+        Self::bad_response_from(response, None).await
+    }
+
+    /// Same as [`Error::bad_response`] for responses from the Nexus api, the Nexus base url can
+    /// differ per [`Config`](crate::services::nexus::Config) so it is not inferred from the url
+    pub(crate) async fn bad_nexus_response(response: reqwest::Response) -> Self {
+        Self::bad_response_from(response, Some("Nexus")).await
+    }
+
+    async fn bad_response_from(response: reqwest::Response, service: Option<&'static str>) -> Self {
+        let status = response.status();
+        let endpoint = response.url().to_string();
+        match response.text().await {
+            Ok(body) => Error::BadResponse {
+                status,
+                endpoint,
+                body,
+                service,
+            },
+            Err(err) => Error::from(err),
+        }
+    }
+
     fn msg(&self) -> Cow<'_, str> {
         match self {
             Error::Io(err) => Cow::Owned(err.to_string()),
             Error::Missing(msg) => Cow::Borrowed(*msg),
             Error::NotSetup(msg) => Cow::Borrowed(*msg),
-            Error::BadResponse(msg) => Cow::Borrowed(msg.as_str()),
+            Error::MissingPermissions(permissions) => Cow::Owned(format!(
+                "GitHub token is missing repository permission(s): {}. Edit the token at \
+                https://github.com/settings/personal-access-tokens, then under 'Repository \
+                permissions' set each to 'Read and write'",
+                permissions.join(", ")
+            )),
+            Error::NotFound(url) => Cow::Owned(format!("Could not find mod on Nexus: {url}")),
+            Error::Restricted { url, reason } => Cow::Owned(format!(
+                "Mod is hidden or requires additional permissions to view, this is a restriction \
+                on the mod not a problem with your api key: {url}{}",
+                reason
+                    .as_deref()
+                    .map(|reason| format!(" (Nexus: {reason})"))
+                    .unwrap_or_default()
+            )),
+            Error::BadResponse {
+                status,
+                endpoint,
+                body,
+                service,
+            } => {
+                let (inferred, path) = match reqwest::Url::parse(endpoint) {
+                    Ok(url) if is_gitlab_endpoint(endpoint) => ("GitLab", url.path().to_string()),
+                    Ok(url) if is_http_endpoint(endpoint) => ("Endpoint", url.path().to_string()),
+                    Ok(url) => ("GitHub", url.path().to_string()),
+                    Err(_) => ("GitHub", endpoint.clone()),
+                };
+                let service = service.unwrap_or(inferred);
+                Cow::Owned(format!("{service} {} on {path}: {body}", status.as_u16()))
+            }
+            Error::InvalidResponse(msg) => Cow::Borrowed(msg.as_str()),
+            Error::RateLimited { retry_after } => Cow::Owned(format!(
+                "Nexus api rate limit reached, try again in {}s",
+                retry_after.as_secs()
+            )),
             Error::Reqwest(err) => Cow::Owned(err.to_string()),
+            Error::Timeout(err) => Cow::Owned(match err.url() {
+                Some(url) => {
+                    format!("Request to {url} timed out, use '--timeout' to allow more time")
+                }
+                None => String::from("Request timed out, use '--timeout' to allow more time"),
+            }),
+            Error::Connect(err) => Cow::Owned(match err.url().and_then(|url| url.host_str()) {
+                Some(host) => format!("Could not connect to {host}, check your network connection"),
+                None => String::from("Could not connect, check your network connection"),
+            }),
             Error::SerdeJson(err) => Cow::Owned(err.to_string()),
             Error::Decode(err) => Cow::Owned(err.to_string()),
             Error::Encrypt(err) => Cow::Owned(err.to_string()),
@@ -78,23 +179,111 @@ impl Error {
 
 impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.msg())
+        write!(f, "{}", redact(&self.msg()))
     }
 }
 
 impl Debug for Error {
+    /// Every variant is passed through [`redact`], wrapped errors such as [`reqwest::Error`] include
+    /// the full request url in their debug output
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            Error::Io(err) => format!("{err:?}"),
+            Error::Missing(msg) => msg.to_string(),
+            Error::NotSetup(msg) => msg.to_string(),
+            Error::MissingPermissions(permissions) => {
+                format!("MissingPermissions({permissions:?})")
+            }
+            Error::NotFound(url) => format!("NotFound({url})"),
+            Error::Restricted { url, reason } => {
+                format!("Restricted {{ url: {url}, reason: {reason:?} }}")
+            }
+            Error::BadResponse {
+                status,
+                endpoint,
+                body,
+                ..
+            } => {
+                format!("BadResponse {{ status: {status:?}, endpoint: {endpoint}, body: {body} }}")
+            }
+            Error::InvalidResponse(msg) => msg.clone(),
+            Error::RateLimited { retry_after } => {
+                format!("RateLimited {{ retry_after: {retry_after:?} }}")
+            }
+            Error::Reqwest(err) | Error::Timeout(err) | Error::Connect(err) => format!("{err:?}"),
+            Error::SerdeJson(err) => format!("{err:?}"),
+            Error::Decode(err) => format!("{err:?}"),
+            Error::Encrypt(err) => format!("{err:?}"),
+            Error::Env(err) => format!("{err:?}"),
+            Error::Join(err) => format!("{err:?}"),
+        };
+        write!(f, "{}", redact(&msg))
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            Error::Io(err) => write!(f, "{err:?}"),
-            Error::Missing(msg) => write!(f, "{msg}"),
-            Error::NotSetup(msg) => write!(f, "{msg}"),
-            Error::BadResponse(msg) => write!(f, "{msg}"),
-            Error::Reqwest(err) => write!(f, "{err:?}"),
-            Error::SerdeJson(err) => write!(f, "{err:?}"),
-            Error::Decode(err) => write!(f, "{err:?}"),
-            Error::Encrypt(err) => write!(f, "{err:?}"),
-            Error::Env(err) => write!(f, "{err:?}"),
-            Error::Join(err) => write!(f, "{err:?}"),
+            Error::Io(err) => Some(err),
+            Error::SerdeJson(err) => Some(err),
+            Error::Reqwest(err) | Error::Timeout(err) | Error::Connect(err) => Some(err),
+            Error::Decode(err) => Some(err),
+            Error::Encrypt(err) => Some(err),
+            Error::Env(err) => Some(err),
+            Error::Join(err) => Some(err),
+            Error::BadResponse { .. }
+            | Error::InvalidResponse(_)
+            | Error::RateLimited { .. }
+            | Error::NotFound(_)
+            | Error::Restricted { .. }
+            | Error::NotSetup(_)
+            | Error::Missing(_)
+            | Error::MissingPermissions(_) => None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bad_response(endpoint: &str, service: Option<&'static str>) -> Error {
+        Error::BadResponse {
+            status: StatusCode::UNAUTHORIZED,
+            endpoint: endpoint.to_string(),
+            body: String::from("denied"),
+            service,
+        }
+    }
+
+    #[test]
+    fn debug_output_is_redacted() {
+        let key = &crate::VARS
+            .get_or_init(|| crate::StartupVars {
+                nexus_key: String::from("debug-redact-key"),
+                ..Default::default()
+            })
+            .nexus_key;
+        assert!(!key.is_empty());
+
+        let io = Error::Io(io::Error::other(format!("https://example.com/?key={key}")));
+        let invalid = Error::InvalidResponse(format!("token {key} was refused"));
+        for err in [io, invalid] {
+            let debug = format!("{err:?}");
+            assert!(!debug.contains(key.as_str()), "leaked: {debug}");
+            assert!(debug.contains("***"));
+        }
+    }
+
+    #[test]
+    fn nexus_label_does_not_depend_on_base_url() {
+        let err = bad_response(
+            "http://127.0.0.1:8765/v1/users/validate.json",
+            Some("Nexus"),
+        );
+        assert_eq!(err.msg(), "Nexus 401 on /v1/users/validate.json: denied");
+
+        let err = bad_response("https://api.github.com/user", None);
+        assert_eq!(err.msg(), "GitHub 401 on /user: denied");
+    }
+}
@@ -1,6 +1,6 @@
-use crate::models::cli::Mod;
+use crate::models::cli::{Backend, Mod};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Deserialize, Serialize, Default, Clone)]
 #[serde(default)]
@@ -8,26 +8,152 @@ pub struct Input {
     pub git_token: String,
     pub nexus_key: String,
     pub gist_id: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub gist_file: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub gist_public: bool,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub gist_description: String,
     pub owner: String,
     pub repo: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub git_base_url: String,
+    #[serde(skip_serializing_if = "Backend::is_github")]
+    pub backend: Backend,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub gitlab_token: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub gitlab_base_url: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub endpoint_url: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub endpoint_token: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub history_limit: Option<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<Author>,
     pub mods: Vec<Mod>,
 }
 
+/// Mod author registered with `add-author`, stored so mods they publish later can be picked up
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Author {
+    pub domain: String,
+    pub name: String,
+}
+
+/// Nexus usernames and game domains are case-insensitive
+impl PartialEq for Author {
+    fn eq(&self, other: &Self) -> bool {
+        self.domain.eq_ignore_ascii_case(&other.domain)
+            && self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+impl Eq for Author {}
+
 #[derive(Serialize, Deserialize, Default, Clone)]
 pub struct ModDetails {
     pub name: String,
-    #[serde(skip_deserializing)]
+    #[serde(default)]
     #[serde(skip_serializing_if = "String::is_empty")]
     pub url: String,
-    #[serde(skip_serializing)]
+    #[serde(skip)]
+    pub label: Option<String>,
+    /// Display name of the game the mod is made for
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub game: String,
+    #[serde(skip_serializing, default)]
     pub uid: u64,
     pub mod_downloads: usize,
     pub mod_unique_downloads: usize,
+    #[serde(default, alias = "endorsement_count")]
+    pub endorsements: usize,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub version: String,
+    /// Total and unique downloads as a single message, a dynamic json badge can only read one field
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub downloads_combined: String,
+}
+
+/// Contents of the remote gist and local output file
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(from = "OutputFormat")]
+pub struct Output {
+    /// Version of the output format, see: `OUTPUT_SCHEMA`
+    pub schema: u32,
+    pub generated_by: String,
+    /// UTC date and time the download counts were retrieved
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub updated_at: String,
+    pub mods: BTreeMap<String, ModDetails>,
+}
+
+/// Accepts both the current envelope and the bare map of mods written before `schema` existed
+#[derive(Deserialize, Clone)]
+#[serde(untagged)]
+enum OutputFormat {
+    Envelope {
+        schema: u32,
+        #[serde(default)]
+        generated_by: String,
+        #[serde(default)]
+        updated_at: String,
+        mods: BTreeMap<String, ModDetails>,
+    },
+    Legacy {
+        #[serde(default)]
+        updated_at: String,
+        #[serde(flatten)]
+        mods: BTreeMap<String, ModDetails>,
+    },
+}
+
+impl From<OutputFormat> for Output {
+    fn from(value: OutputFormat) -> Self {
+        match value {
+            OutputFormat::Envelope {
+                schema,
+                generated_by,
+                updated_at,
+                mods,
+            } => Output {
+                schema,
+                generated_by,
+                updated_at,
+                mods,
+            },
+            OutputFormat::Legacy { updated_at, mods } => Output {
+                schema: 0,
+                generated_by: String::new(),
+                updated_at,
+                mods,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Seconds since the unix epoch
+    pub timestamp: u64,
+    pub counts: BTreeMap<String, SnapshotCounts>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotCounts {
+    pub downloads: usize,
+    pub unique: usize,
 }
 
 #[derive(Deserialize)]
 pub struct GistResponse {
     pub id: String,
+    #[serde(default)]
+    pub description: Option<String>,
     pub files: HashMap<String, FileDetails>,
 }
 
@@ -38,13 +164,81 @@ pub struct FileDetails {
 }
 
 #[derive(Deserialize)]
+pub struct SnippetResponse {
+    pub id: u64,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub files: Vec<SnippetFile>,
+}
+
+#[derive(Deserialize)]
+pub struct SnippetFile {
+    pub path: String,
+    pub raw_url: String,
+}
+
+#[derive(Serialize, Deserialize)]
 pub struct Version {
     pub latest: String,
     pub message: String,
 }
 
+/// Url badges read counts from, cached so badges can be written without fetching the remote. Only
+/// used while `gist_id` matches the stored id
+#[derive(Serialize, Deserialize)]
+pub struct RemoteCache {
+    pub gist_id: String,
+    pub universal_url: String,
+}
+
+/// Result of the last program version check, allows skipping or conditionally sending the request
+#[derive(Serialize, Deserialize)]
+pub struct VersionCheck {
+    /// Seconds since the unix epoch
+    pub checked_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    #[serde(flatten)]
+    pub version: Version,
+}
+
 #[derive(Deserialize)]
 pub struct RepositoryPublicKey {
     pub key_id: String,
     pub key: String,
 }
+
+#[derive(Deserialize)]
+pub struct WorkflowDetails {
+    pub state: String,
+    pub html_url: String,
+}
+
+#[derive(Deserialize)]
+pub struct WorkflowRuns {
+    pub workflow_runs: Vec<WorkflowRun>,
+}
+
+#[derive(Deserialize)]
+pub struct WorkflowRun {
+    pub status: String,
+    pub conclusion: Option<String>,
+    pub event: String,
+    pub run_started_at: String,
+    pub html_url: String,
+}
+
+#[derive(Deserialize)]
+pub struct CacheList {
+    pub total_count: usize,
+    pub actions_caches: Vec<CacheEntry>,
+}
+
+#[derive(Deserialize)]
+pub struct CacheEntry {
+    pub id: u64,
+    pub key: String,
+}
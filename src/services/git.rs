@@ -1,106 +1,236 @@
 use crate::{
+    client_builder,
     models::{
         cli::Workflow,
         error::Error,
-        json_data::{FileDetails, GistResponse, RepositoryPublicKey},
+        json_data::{
+            CacheEntry, CacheList, FileDetails, GistResponse, RepositoryPublicKey, WorkflowDetails,
+            WorkflowRun, WorkflowRuns,
+        },
     },
-    verify_repo, CREATED_RESPONSE, OK_RESPONSE, UPDATED_RESPONSE, VARS,
+    verify_repo, StartupVars, CREATED_RESPONSE, FORBIDDEN_RESPONSE, NOT_FOUND_RESPONSE,
+    OK_RESPONSE, UPDATED_RESPONSE, VARS,
 };
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use crypto_box::{aead::OsRng, PublicKey};
+use log::info;
 use reqwest::header::{HeaderMap, HeaderValue};
 use std::{
     borrow::Cow,
     fmt::Display,
     io::{self, ErrorKind},
+    sync::OnceLock,
 };
 
 const GIT_BASE_URL: &str = "https://api.github.com";
 const GIT_API_VER: &str = "2022-11-28";
 
+/// Max page size supported by the GitHub caches api
+const CACHES_PER_PAGE: usize = 100;
+
 const GIST_NAME: &str = "nexus_badges.json";
-const GIST_DESC: &str = "Private gist to be used as a json endpoint for badge download counters";
+const GIST_DESC: &str = "Gist to be used as a json endpoint for badge download counters";
 
 const WORKFLOW_NAME: &str = "automation.yml";
 const RAW: &str = "/raw/";
 
 impl GistResponse {
     fn file_details(&self) -> Result<&FileDetails, Error> {
-        self.files.get(GIST_NAME).ok_or_else(|| {
-            Error::BadResponse(format!(
-                "Gist response did not contains details about any file with the name: {GIST_NAME}"
+        let file_name = gist_file_name();
+        self.files.get(file_name).ok_or_else(|| {
+            Error::InvalidResponse(format!(
+                "Gist response did not contains details about any file with the name: {file_name}"
             ))
         })
     }
 
+    /// Gist raw urls are pinned to a revision so they are cut back to the latest revision, other
+    /// backends already report a stable url
     pub fn universal_url(&self) -> Result<&str, Error> {
-        self.file_details().map(|entry| {
-            let i = entry.raw_url.find(RAW).expect("always contains `RAW`");
-            &entry.raw_url[..i + (RAW.len() - '/'.len_utf8())]
-        })
+        let backend = VARS.get().expect("set on startup").backend;
+        self.file_details()
+            .map(|entry| match entry.raw_url.find(RAW) {
+                Some(i) if backend.is_github() => {
+                    &entry.raw_url[..i + (RAW.len() - '/'.len_utf8())]
+                }
+                _ => entry.raw_url.as_str(),
+            })
     }
 
     pub fn content(&self) -> Result<&str, Error> {
         self.file_details().map(|entry| entry.content.as_str())
     }
+
+    /// Only a user set description is compared, so remote runs never reset it
+    pub fn description_outdated(&self) -> bool {
+        let desc = &VARS.get().expect("set on startup").gist_description;
+        !desc.is_empty() && self.description.as_ref() != Some(desc)
+    }
+}
+
+impl StartupVars {
+    #[inline]
+    fn git_base_url(&self) -> &str {
+        if self.git_base_url.is_empty() {
+            return GIT_BASE_URL;
+        }
+        &self.git_base_url
+    }
+}
+
+#[inline]
+pub(crate) fn gist_file_name() -> &'static str {
+    let vars = VARS.get().expect("set on startup");
+    if vars.gist_file.is_empty() {
+        return GIST_NAME;
+    }
+    &vars.gist_file
+}
+
+#[inline]
+pub(crate) fn gist_description() -> &'static str {
+    let vars = VARS.get().expect("set on startup");
+    if vars.gist_description.is_empty() {
+        return GIST_DESC;
+    }
+    &vars.gist_description
+}
+
+#[inline]
+fn git_base_url() -> &'static str {
+    VARS.get().expect("set on startup").git_base_url()
+}
+
+/// Gist ids are hex strings, 20 or 32 characters long for current gists but only digits for the
+/// oldest ones. GitLab snippet ids are numeric so this holds for them too
+pub(crate) fn is_gist_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= 40 && id.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Reduces a gist url pasted from the browser to the gist id it contains, other input is returned
+/// as is. Handles `gist.github.com/<id>`, `gist.github.com/<user>/<id>`, clone urls ending in
+/// `.git`, and raw urls `gist.githubusercontent.com/<user>/<id>/raw/...`. A lone segment that is
+/// not a gist id is a profile url `gist.github.com/<user>`, which is returned as is
+pub(crate) fn strip_gist_url(s: &str) -> &str {
+    let host_and_path = s
+        .trim_start_matches("https://")
+        .trim_start_matches("http://");
+    let Some(path) = host_and_path
+        .strip_prefix("gist.github.com/")
+        .or_else(|| host_and_path.strip_prefix("gist.githubusercontent.com/"))
+    else {
+        return s;
+    };
+
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+    match (segments.next(), segments.next()) {
+        (Some(_user), Some(id)) => id.strip_suffix(".git").unwrap_or(id),
+        (Some(segment), None) => {
+            let id = segment.strip_suffix(".git").unwrap_or(segment);
+            if is_gist_id(id) {
+                id
+            } else {
+                s
+            }
+        }
+        (None, _) => s,
+    }
 }
 
-pub fn gist_id_endpoint() -> String {
+fn gist_id_endpoint() -> String {
     format!(
-        "{GIT_BASE_URL}/gists/{}",
+        "{}/gists/{}",
+        git_base_url(),
         VARS.get().expect("set on startup").gist_id
     )
 }
 
 fn gist_endpoint() -> String {
-    format!("{GIT_BASE_URL}/gists",)
+    format!("{}/gists", git_base_url())
 }
 
 fn repository_public_key_endpoint() -> String {
     let vars = VARS.get().expect("set on startup");
     format!(
-        "{GIT_BASE_URL}/repos/{}/{}/actions/secrets/public-key",
-        vars.owner, vars.repo
+        "{}/repos/{}/{}/actions/secrets/public-key",
+        vars.git_base_url(),
+        vars.owner,
+        vars.repo
     )
 }
 
 fn repository_secret_endpoint(secret_name: &str) -> String {
     let vars = VARS.get().expect("set on startup");
     format!(
-        "{GIT_BASE_URL}/repos/{}/{}/actions/secrets/{secret_name}",
-        vars.owner, vars.repo
+        "{}/repos/{}/{}/actions/secrets/{secret_name}",
+        vars.git_base_url(),
+        vars.owner,
+        vars.repo
     )
 }
 
 fn repository_variables_endpoint() -> String {
     let vars = VARS.get().expect("set on startup");
     format!(
-        "{GIT_BASE_URL}/repos/{}/{}/actions/variables",
-        vars.owner, vars.repo
+        "{}/repos/{}/{}/actions/variables",
+        vars.git_base_url(),
+        vars.owner,
+        vars.repo
     )
 }
 
 fn repository_variable_endpoint(var: &str) -> String {
     let vars = VARS.get().expect("set on startup");
     format!(
-        "{GIT_BASE_URL}/repos/{}/{}/actions/variables/{var}",
-        vars.owner, vars.repo
+        "{}/repos/{}/{}/actions/variables/{var}",
+        vars.git_base_url(),
+        vars.owner,
+        vars.repo
     )
 }
 
+fn repository_caches_endpoint() -> String {
+    let vars = VARS.get().expect("set on startup");
+    format!(
+        "{}/repos/{}/{}/actions/caches",
+        vars.git_base_url(),
+        vars.owner,
+        vars.repo
+    )
+}
+
+fn repository_cache_id_endpoint(id: u64) -> String {
+    format!("{}/{id}", repository_caches_endpoint())
+}
+
 fn repository_cache_endpoint(key: &str) -> String {
     let vars = VARS.get().expect("set on startup");
     format!(
-        "{GIT_BASE_URL}/repos/{}/{}/actions/caches?key={key}",
-        vars.owner, vars.repo
+        "{}/repos/{}/{}/actions/caches?key={key}",
+        vars.git_base_url(),
+        vars.owner,
+        vars.repo
+    )
+}
+
+fn workflow_endpoint() -> String {
+    let vars = VARS.get().expect("set on startup");
+    format!(
+        "{}/repos/{}/{}/actions/workflows/{WORKFLOW_NAME}",
+        vars.git_base_url(),
+        vars.owner,
+        vars.repo
     )
 }
 
 fn workflow_endpoint_state(state: Workflow) -> String {
     let vars = VARS.get().expect("set on startup");
     format!(
-        "{GIT_BASE_URL}/repos/{}/{}/actions/workflows/{WORKFLOW_NAME}/{state}",
-        vars.owner, vars.repo
+        "{}/repos/{}/{}/actions/workflows/{WORKFLOW_NAME}/{state}",
+        vars.git_base_url(),
+        vars.owner,
+        vars.repo
     )
 }
 
@@ -112,18 +242,30 @@ impl Display for Workflow {
             match self {
                 Workflow::Enable => "enable",
                 Workflow::Disable => "disable",
+                Workflow::Status => "status",
             }
         )
     }
 }
 
+/// Client with the GitHub api headers set by default. Built on first use, as the headers depend on
+/// `VARS` which is not yet set when the shared client is initialized
+fn git_client() -> &'static reqwest::Client {
+    static GIT_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    GIT_CLIENT.get_or_init(|| {
+        client_builder()
+            .default_headers(git_header())
+            .build()
+            .expect("builds with the same config as the shared client")
+    })
+}
+
 fn git_token_h_key() -> String {
     format!("Bearer {}", VARS.get().expect("set on startup").git_token)
 }
 
 fn git_header() -> reqwest::header::HeaderMap {
     [
-        ("User-Agent", Cow::Borrowed(env!("CARGO_PKG_NAME"))),
         ("Accept", Cow::Borrowed("application/vnd.github+json")),
         ("Authorization", Cow::Owned(git_token_h_key())),
         ("X-GitHub-Api-Version", Cow::Borrowed(GIT_API_VER)),
@@ -144,58 +286,56 @@ fn git_header() -> reqwest::header::HeaderMap {
 pub async fn set_workflow_state(state: Workflow) -> Result<(), Error> {
     verify_repo()?;
 
-    let server_response = reqwest::Client::new()
+    let server_response = git_client()
         .put(workflow_endpoint_state(state))
-        .headers(git_header())
         .send()
         .await?;
 
     if server_response.status() != UPDATED_RESPONSE {
-        return Err(Error::BadResponse(server_response.text().await?));
+        return Err(Error::bad_response(server_response).await);
     }
 
-    println!("GitHub automation workflow: {state}d");
+    info!("GitHub automation workflow: {state}d");
     Ok(())
 }
 
 pub async fn set_repository_variable(name: &str, value: &str) -> Result<(), Error> {
     let build = |request: reqwest::RequestBuilder| {
         request
-            .headers(git_header())
             .json(&serde_json::json!({
                 "name": name,
                 "value": value,
             }))
             .send()
     };
-    let update_request = reqwest::Client::new().patch(repository_variable_endpoint(name));
+    let update_request = git_client().patch(repository_variable_endpoint(name));
     let update_response = build(update_request).await?;
 
     if update_response.status() == UPDATED_RESPONSE {
-        println!("Repository variable: {name}, updated");
+        info!("Repository variable: {name}, updated");
         return Ok(());
     }
 
-    let create_request = reqwest::Client::new().post(repository_variables_endpoint());
+    let create_request = git_client().post(repository_variables_endpoint());
     let create_response = build(create_request).await?;
 
     if create_response.status() == CREATED_RESPONSE {
-        println!("Repository variable: {name}, created");
+        info!("Repository variable: {name}, created");
         return Ok(());
     }
 
-    Err(Error::BadResponse(create_response.text().await?))
+    Err(Error::bad_response(create_response).await)
 }
 
 pub async fn create_remote(content: String) -> Result<GistResponse, Error> {
-    let server_response = reqwest::Client::new()
+    let public = VARS.get().expect("set on startup").gist_public;
+    let server_response = git_client()
         .post(gist_endpoint())
-        .headers(git_header())
         .json(&serde_json::json!({
-            "description": GIST_DESC,
-            "public": false,
+            "description": gist_description(),
+            "public": public,
             "files": {
-                GIST_NAME: {
+                gist_file_name(): {
                     "content": content
                 }
             }
@@ -204,10 +344,14 @@ pub async fn create_remote(content: String) -> Result<GistResponse, Error> {
         .await?;
 
     if server_response.status() != CREATED_RESPONSE {
-        return Err(Error::BadResponse(server_response.text().await?));
+        return Err(Error::bad_response(server_response).await);
     }
 
-    println!("New private gist created with name: {GIST_NAME}");
+    info!(
+        "New {} gist created with name: {}",
+        if public { "public" } else { "private" },
+        gist_file_name()
+    );
 
     server_response
         .json::<GistResponse>()
@@ -215,25 +359,30 @@ pub async fn create_remote(content: String) -> Result<GistResponse, Error> {
         .map_err(Error::from)
 }
 
-pub async fn update_remote(gist_endpoint: &str, content: String) -> Result<GistResponse, Error> {
-    let server_response = reqwest::Client::new()
-        .patch(gist_endpoint)
-        .headers(git_header())
-        .json(&serde_json::json!({
-            "files": {
-                GIST_NAME: {
-                    "content": content
-                }
+pub async fn update_remote(content: String) -> Result<GistResponse, Error> {
+    let mut body = serde_json::json!({
+        "files": {
+            gist_file_name(): {
+                "content": content
             }
-        }))
+        }
+    });
+    let vars = VARS.get().expect("set on startup");
+    if !vars.gist_description.is_empty() {
+        body["description"] = serde_json::Value::from(vars.gist_description.as_str());
+    }
+
+    let server_response = git_client()
+        .patch(gist_id_endpoint())
+        .json(&body)
         .send()
         .await?;
 
     if server_response.status() != OK_RESPONSE {
-        return Err(Error::BadResponse(server_response.text().await?));
+        return Err(Error::bad_response(server_response).await);
     }
 
-    println!("Remote gist successfully updated");
+    info!("Remote gist successfully updated");
 
     server_response
         .json::<GistResponse>()
@@ -241,15 +390,49 @@ pub async fn update_remote(gist_endpoint: &str, content: String) -> Result<GistR
         .map_err(Error::from)
 }
 
-pub async fn get_remote(gist_endpoint: &str) -> Result<GistResponse, Error> {
-    let server_response = reqwest::Client::new()
-        .get(gist_endpoint)
-        .headers(git_header())
-        .send()
-        .await?;
+/// Fetches the state of the automation workflow along with its most recent run, `None` if the
+/// workflow has never run
+pub async fn get_workflow_status() -> Result<(WorkflowDetails, Option<WorkflowRun>), Error> {
+    verify_repo()?;
+
+    let details = async {
+        let response = git_client().get(workflow_endpoint()).send().await?;
+        if response.status() == NOT_FOUND_RESPONSE {
+            return Err(Error::Missing(
+                "'automation.yml' was not found in the set repository, use command \
+                'set-arg --owner --repo' to change the repository",
+            ));
+        }
+        if response.status() != OK_RESPONSE {
+            return Err(Error::bad_response(response).await);
+        }
+        response
+            .json::<WorkflowDetails>()
+            .await
+            .map_err(Error::from)
+    };
+
+    let latest_run = async {
+        let response = git_client()
+            .get(format!("{}/runs", workflow_endpoint()))
+            .query(&[("per_page", "1")])
+            .send()
+            .await?;
+        if response.status() != OK_RESPONSE {
+            return Err(Error::bad_response(response).await);
+        }
+        let runs = response.json::<WorkflowRuns>().await?;
+        Ok(runs.workflow_runs.into_iter().next())
+    };
+
+    tokio::try_join!(details, latest_run)
+}
+
+pub async fn get_remote() -> Result<GistResponse, Error> {
+    let server_response = git_client().get(gist_id_endpoint()).send().await?;
 
     if server_response.status() != OK_RESPONSE {
-        return Err(Error::BadResponse(server_response.text().await?));
+        return Err(Error::bad_response(server_response).await);
     }
 
     server_response
@@ -258,15 +441,66 @@ pub async fn get_remote(gist_endpoint: &str) -> Result<GistResponse, Error> {
         .map_err(Error::from)
 }
 
+pub async fn delete_remote() -> Result<(), Error> {
+    let server_response = git_client().delete(gist_id_endpoint()).send().await?;
+
+    if server_response.status() != UPDATED_RESPONSE {
+        return Err(Error::bad_response(server_response).await);
+    }
+
+    info!("Remote gist successfully deleted");
+    Ok(())
+}
+
+/// Makes a cheap read request for each repository permission GitHub actions setup relies on, so a
+/// token missing any of them is reported by name rather than by an opaque 403 halfway through setup.
+/// Read access passing does not guarantee write access, but a refused read always means the
+/// permission is missing
+pub async fn verify_actions_permissions() -> Result<(), Error> {
+    verify_repo()?;
+
+    let check = |permission: &'static str, endpoint: String| async move {
+        let response = git_client()
+            .get(endpoint)
+            .query(&[("per_page", "1")])
+            .send()
+            .await?;
+        match response.status().as_u16() {
+            OK_RESPONSE => Ok(None),
+            FORBIDDEN_RESPONSE => Ok(Some(permission)),
+            NOT_FOUND_RESPONSE => Err(Error::Missing(
+                "GitHub token can not access the set repository, or it does not contain \
+                'automation.yml'. Check that 'Repository access' of the token includes the \
+                repository, use command 'set-arg --owner --repo' to change the repository",
+            )),
+            _ => Err(Error::bad_response(response).await),
+        }
+    };
+
+    let (actions, secrets, variables) = tokio::join!(
+        check("Actions", workflow_endpoint()),
+        check("Secrets", repository_public_key_endpoint()),
+        check("Variables", repository_variables_endpoint()),
+    );
+
+    let missing = [actions?, secrets?, variables?]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        return Err(Error::MissingPermissions(missing));
+    }
+    Ok(())
+}
+
 pub async fn get_public_key() -> Result<RepositoryPublicKey, Error> {
-    let server_response = reqwest::Client::new()
+    let server_response = git_client()
         .get(repository_public_key_endpoint())
-        .headers(git_header())
         .send()
         .await?;
 
     if server_response.status() != OK_RESPONSE {
-        return Err(Error::BadResponse(server_response.text().await?));
+        return Err(Error::bad_response(server_response).await);
     }
 
     server_response
@@ -282,9 +516,8 @@ pub async fn set_repository_secret(
 ) -> Result<(), Error> {
     let encrypted_secret = encrypt_secret(secret, &public_key.key)?;
 
-    let server_response = reqwest::Client::new()
+    let server_response = git_client()
         .put(repository_secret_endpoint(secret_name))
-        .headers(git_header())
         .json(&serde_json::json!({
             "encrypted_value": encrypted_secret,
             "key_id": public_key.key_id,
@@ -292,12 +525,12 @@ pub async fn set_repository_secret(
         .send()
         .await?;
 
-    let print_status = |status: &str| println!("Repository secret: {secret_name}, {status}");
+    let print_status = |status: &str| info!("Repository secret: {secret_name}, {status}");
 
     match server_response.status() {
         s if s == CREATED_RESPONSE => print_status("created"),
         s if s == UPDATED_RESPONSE => print_status("updated"),
-        _ => return Err(Error::BadResponse(server_response.text().await?)),
+        _ => return Err(Error::bad_response(server_response).await),
     }
 
     Ok(())
@@ -314,16 +547,129 @@ fn encrypt_secret(secret: &str, public_key: &str) -> Result<String, Error> {
 }
 
 pub async fn delete_cache_by_key(key: &str) -> Result<(), Error> {
-    let server_response = reqwest::Client::new()
+    let server_response = git_client()
         .delete(repository_cache_endpoint(key))
-        .headers(git_header())
         .send()
         .await?;
 
     if server_response.status() != OK_RESPONSE {
-        return Err(Error::BadResponse(server_response.text().await?));
+        return Err(Error::bad_response(server_response).await);
     }
 
-    println!("Removed old cache with key: {key}");
+    info!("Removed old cache with key: {key}");
     Ok(())
 }
+
+/// Pages through all repository caches, only returning caches whose key starts with `key_prefix`
+/// when given
+pub async fn list_caches(key_prefix: Option<&str>) -> Result<Vec<CacheEntry>, Error> {
+    let mut caches = Vec::new();
+
+    for page in 1.. {
+        let mut request = git_client()
+            .get(repository_caches_endpoint())
+            .query(&[("per_page", CACHES_PER_PAGE), ("page", page)]);
+        if let Some(prefix) = key_prefix {
+            request = request.query(&[("key", prefix)]);
+        }
+
+        let server_response = request.send().await?;
+
+        if server_response.status() != OK_RESPONSE {
+            return Err(Error::bad_response(server_response).await);
+        }
+
+        let list = server_response.json::<CacheList>().await?;
+        let page_len = list.actions_caches.len();
+        caches.extend(list.actions_caches);
+
+        if page_len < CACHES_PER_PAGE || caches.len() >= list.total_count {
+            break;
+        }
+    }
+
+    Ok(caches)
+}
+
+/// Deletes every cache whose key starts with `prefix` other than `keep`, returns the number of
+/// caches removed
+pub async fn delete_caches_by_prefix(prefix: &str, keep: &str) -> Result<usize, Error> {
+    let mut removed = 0;
+
+    for cache in list_caches(Some(prefix)).await? {
+        if cache.key == keep || !cache.key.starts_with(prefix) {
+            continue;
+        }
+
+        let server_response = git_client()
+            .delete(repository_cache_id_endpoint(cache.id))
+            .send()
+            .await?;
+
+        if server_response.status() != UPDATED_RESPONSE {
+            return Err(Error::bad_response(server_response).await);
+        }
+
+        info!("Removed old cache with key: {}", cache.key);
+        removed += 1;
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "aa5a315d61ae9438b18d";
+
+    #[test]
+    fn strips_gist_urls() {
+        let cases = [
+            format!("https://gist.github.com/{ID}"),
+            format!("https://gist.github.com/user/{ID}"),
+            format!("gist.github.com/user/{ID}/"),
+            format!("https://gist.github.com/{ID}.git"),
+            format!("https://gist.github.com/{ID}/"),
+            format!("https://gist.githubusercontent.com/user/{ID}/raw/output.json"),
+            format!("https://gist.github.com/user/{ID}?permalink_comment_id=1"),
+            format!("https://gist.github.com/user/{ID}#file-output-json"),
+            ID.to_string(),
+        ];
+        for url in cases {
+            assert_eq!(strip_gist_url(&url), ID, "{url}");
+            assert!(is_gist_id(strip_gist_url(&url)), "{url}");
+        }
+    }
+
+    #[test]
+    fn leaves_other_input() {
+        assert_eq!(
+            strip_gist_url("https://github.com/user/repo"),
+            "https://github.com/user/repo"
+        );
+        assert_eq!(
+            strip_gist_url("https://gist.github.com/"),
+            "https://gist.github.com/"
+        );
+        assert_eq!(
+            strip_gist_url("https://gist.github.com/octocat"),
+            "https://gist.github.com/octocat"
+        );
+        assert_eq!(
+            strip_gist_url("gist.github.com/octocat/"),
+            "gist.github.com/octocat/"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_ids() {
+        assert!(!is_gist_id(""));
+        assert!(!is_gist_id("not-a-gist-id"));
+        assert!(!is_gist_id(strip_gist_url(
+            "https://gist.github.com/user/xyz123"
+        )));
+        assert!(!is_gist_id(&"a".repeat(41)));
+        assert!(!is_gist_id("https://github.com/user/repo"));
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{
+    client_builder,
+    models::{
+        error::Error,
+        json_data::{FileDetails, GistResponse, SnippetResponse},
+    },
+    services::git::{gist_description, gist_file_name},
+    StartupVars, CREATED_RESPONSE, OK_RESPONSE, UPDATED_RESPONSE, VARS,
+};
+use log::info;
+use reqwest::header::{HeaderMap, HeaderValue};
+use std::{collections::HashMap, sync::OnceLock};
+
+const GITLAB_BASE_URL: &str = "https://gitlab.com/api/v4";
+
+impl StartupVars {
+    #[inline]
+    pub(crate) fn gitlab_base_url(&self) -> &str {
+        if self.gitlab_base_url.is_empty() {
+            return GITLAB_BASE_URL;
+        }
+        &self.gitlab_base_url
+    }
+}
+
+impl SnippetResponse {
+    /// Snippet responses do not include file contents, so `content` is attached to the entry
+    /// matching `gist_file_name`
+    fn into_gist(self, content: String) -> GistResponse {
+        let file_name = gist_file_name();
+        GistResponse {
+            id: self.id.to_string(),
+            description: Some(self.title),
+            files: self
+                .files
+                .into_iter()
+                .filter(|file| file.path == file_name)
+                .map(|file| {
+                    let details = FileDetails {
+                        raw_url: file.raw_url,
+                        content: content.clone(),
+                    };
+                    (file.path, details)
+                })
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+}
+
+#[inline]
+fn gitlab_base_url() -> &'static str {
+    VARS.get().expect("set on startup").gitlab_base_url()
+}
+
+#[inline]
+pub(crate) fn is_gitlab_endpoint(url: &str) -> bool {
+    VARS.get()
+        .is_some_and(|vars| url.starts_with(vars.gitlab_base_url()))
+}
+
+fn snippets_endpoint() -> String {
+    format!("{}/snippets", gitlab_base_url())
+}
+
+fn snippet_id_endpoint() -> String {
+    format!(
+        "{}/snippets/{}",
+        gitlab_base_url(),
+        VARS.get().expect("set on startup").gist_id
+    )
+}
+
+fn snippet_raw_endpoint() -> String {
+    format!("{}/raw", snippet_id_endpoint())
+}
+
+/// Client with the GitLab api headers set by default, built on first use for the same reason as
+/// the GitHub client
+fn gitlab_client() -> &'static reqwest::Client {
+    static GITLAB_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    GITLAB_CLIENT.get_or_init(|| {
+        let token = &VARS.get().expect("set on startup").gitlab_token;
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "PRIVATE-TOKEN",
+            HeaderValue::from_str(token).expect("tokens are always valid header values"),
+        );
+        client_builder()
+            .default_headers(headers)
+            .build()
+            .expect("builds with the same config as the shared client")
+    })
+}
+
+/// Snippets are always created as public, shields.io can not read private or internal snippets
+pub async fn create_snippet(content: String) -> Result<GistResponse, Error> {
+    let server_response = gitlab_client()
+        .post(snippets_endpoint())
+        .json(&serde_json::json!({
+            "title": gist_description(),
+            "visibility": "public",
+            "files": [{
+                "file_path": gist_file_name(),
+                "content": content
+            }]
+        }))
+        .send()
+        .await?;
+
+    if server_response.status() != CREATED_RESPONSE {
+        return Err(Error::bad_response(server_response).await);
+    }
+
+    info!("New public snippet created with name: {}", gist_file_name());
+
+    let snippet = server_response.json::<SnippetResponse>().await?;
+    Ok(snippet.into_gist(content))
+}
+
+pub async fn update_snippet(content: String) -> Result<GistResponse, Error> {
+    let mut body = serde_json::json!({
+        "files": [{
+            "action": "update",
+            "file_path": gist_file_name(),
+            "content": content
+        }]
+    });
+    let vars = VARS.get().expect("set on startup");
+    if !vars.gist_description.is_empty() {
+        body["title"] = serde_json::Value::from(vars.gist_description.as_str());
+    }
+
+    let server_response = gitlab_client()
+        .put(snippet_id_endpoint())
+        .json(&body)
+        .send()
+        .await?;
+
+    if server_response.status() != OK_RESPONSE {
+        return Err(Error::bad_response(server_response).await);
+    }
+
+    info!("Remote snippet successfully updated");
+
+    let snippet = server_response.json::<SnippetResponse>().await?;
+    Ok(snippet.into_gist(content))
+}
+
+pub async fn get_snippet() -> Result<GistResponse, Error> {
+    let (meta_response, raw_response) = tokio::try_join!(
+        gitlab_client().get(snippet_id_endpoint()).send(),
+        gitlab_client().get(snippet_raw_endpoint()).send()
+    )?;
+
+    if meta_response.status() != OK_RESPONSE {
+        return Err(Error::bad_response(meta_response).await);
+    }
+    if raw_response.status() != OK_RESPONSE {
+        return Err(Error::bad_response(raw_response).await);
+    }
+
+    let (snippet, content) =
+        tokio::try_join!(meta_response.json::<SnippetResponse>(), raw_response.text())?;
+    Ok(snippet.into_gist(content))
+}
+
+pub async fn delete_snippet() -> Result<(), Error> {
+    let server_response = gitlab_client().delete(snippet_id_endpoint()).send().await?;
+
+    if server_response.status() != UPDATED_RESPONSE {
+        return Err(Error::bad_response(server_response).await);
+    }
+
+    info!("Remote snippet successfully deleted");
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use crate::{
+    client,
+    models::{
+        error::Error,
+        json_data::{FileDetails, GistResponse},
+    },
+    services::git::gist_file_name,
+    StartupVars, VARS,
+};
+use log::info;
+use reqwest::header::CONTENT_TYPE;
+use std::collections::HashMap;
+
+impl StartupVars {
+    /// The endpoint has no notion of an id, the url is stored as the id so the rest of the program
+    /// can treat it like any other remote
+    fn endpoint_response(&self, content: String) -> GistResponse {
+        GistResponse {
+            id: self.endpoint_url.clone(),
+            // Endpoints do not store a description, reporting the stored one keeps
+            // `description_outdated` from forcing an update every run
+            description: Some(self.gist_description.clone()),
+            files: HashMap::from([(
+                gist_file_name().to_string(),
+                FileDetails {
+                    raw_url: self.endpoint_url.clone(),
+                    content,
+                },
+            )]),
+        }
+    }
+}
+
+#[inline]
+pub(crate) fn is_http_endpoint(url: &str) -> bool {
+    VARS.get()
+        .is_some_and(|vars| !vars.endpoint_url.is_empty() && url.starts_with(&vars.endpoint_url))
+}
+
+/// Creating and updating are the same request, the endpoint is expected to replace its content
+/// with the body of the PUT
+pub async fn put_endpoint(content: String) -> Result<GistResponse, Error> {
+    let vars = VARS.get().expect("set on startup");
+    let server_response = client()
+        .put(&vars.endpoint_url)
+        .bearer_auth(&vars.endpoint_token)
+        .header(CONTENT_TYPE, "application/json")
+        .body(content.clone())
+        .send()
+        .await?;
+
+    if !server_response.status().is_success() {
+        return Err(Error::bad_response(server_response).await);
+    }
+
+    info!("Remote endpoint successfully updated");
+    Ok(vars.endpoint_response(content))
+}
+
+pub async fn get_endpoint() -> Result<GistResponse, Error> {
+    let vars = VARS.get().expect("set on startup");
+    let server_response = client()
+        .get(&vars.endpoint_url)
+        .bearer_auth(&vars.endpoint_token)
+        .send()
+        .await?;
+
+    if !server_response.status().is_success() {
+        return Err(Error::bad_response(server_response).await);
+    }
+
+    Ok(vars.endpoint_response(server_response.text().await?))
+}
+
+pub async fn delete_endpoint() -> Result<(), Error> {
+    let vars = VARS.get().expect("set on startup");
+    let server_response = client()
+        .delete(&vars.endpoint_url)
+        .bearer_auth(&vars.endpoint_token)
+        .send()
+        .await?;
+
+    if !server_response.status().is_success() {
+        return Err(Error::bad_response(server_response).await);
+    }
+
+    info!("Remote endpoint successfully deleted");
+    Ok(())
+}
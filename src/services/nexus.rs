@@ -1,22 +1,156 @@
 use crate::{
-    models::{cli::Mod, error::Error, json_data::ModDetails},
-    verify_added, verify_nexus, write, OK_RESPONSE, PATHS, TOTAL_KEY, VARS,
+    client,
+    models::{
+        cli::Mod,
+        error::Error,
+        json_data::{ModDetails, Output},
+    },
+    parse_utc_date, unix_timestamp, verify_added, verify_nexus, write, FORBIDDEN_RESPONSE,
+    NOT_FOUND_RESPONSE, OK_RESPONSE, PATHS, TOO_MANY_REQUESTS_RESPONSE, TOTAL_KEY, USER_AGENT,
+    VARS,
 };
+use log::{debug, info, warn};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use serde::Deserialize;
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashMap, HashSet},
     io::{self, ErrorKind},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, LazyLock,
+    },
+    time::Duration,
+};
+use tokio::{
+    sync::{OnceCell, Semaphore},
+    task::JoinSet,
 };
-use tokio::task::JoinSet;
 
 const NEXUS_BASE_URL: &str = "https://api.nexusmods.com";
+/// Overrides `NEXUS_BASE_URL`, allows pointing requests at a mock server or proxy
+const ENV_NAME_NEXUS_API_URL: &str = "NEXUS_BADGES_NEXUS_API_URL";
+
+static BASE_URL: LazyLock<String> = LazyLock::new(|| {
+    std::env::var(ENV_NAME_NEXUS_API_URL)
+        .ok()
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| String::from(NEXUS_BASE_URL))
+});
+
+/// Request timeout of clients built by [`Config::new`]
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Number of times a request is resent after a connection error, timeout, or 5xx response
+const MAX_RETRIES: u32 = 3;
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Wait used when a rate limited response includes neither a `Retry-After` header nor the reset
+/// time of an exhausted rate limit
+const DEFAULT_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+/// Rate limits that reset further out than this are reported instead of waited on
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(120);
+
+/// Remaining and reset header names of the hourly and daily Nexus rate limits
+const RATE_LIMIT_BUCKETS: [(&str, &str); 2] = [
+    ("x-rl-hourly-remaining", "x-rl-hourly-reset"),
+    ("x-rl-daily-remaining", "x-rl-daily-reset"),
+];
+
+/// Upper limit of requests in flight at once, keeps large mod lists under the Nexus rate limit
+const MAX_CONCURRENT_REQUESTS: usize = 8;
+
+static SKIP_ERRORS: AtomicBool = AtomicBool::new(false);
+
+/// When set, mods that fail to update are reported and left out of the output instead of aborting
+/// the run
+pub fn set_skip_errors(skip: bool) {
+    SKIP_ERRORS.store(skip, Ordering::Relaxed);
+}
+
+/// Everything a Nexus request needs, passed in explicitly so the download counts can be fetched
+/// from other tools without the startup state of the binary
+#[derive(Clone)]
+pub struct Config {
+    pub nexus_key: String,
+    /// Root of the Nexus api [Default: `NEXUS_BADGES_NEXUS_API_URL` or https://api.nexusmods.com]
+    pub base_url: String,
+    /// Report mods that fail to update and leave them out instead of failing the whole fetch
+    pub skip_errors: bool,
+    client: reqwest::Client,
+}
+
+impl Config {
+    /// Builds a config with its own http client, identified by the same User-Agent as the binary
+    pub fn new(nexus_key: impl Into<String>) -> reqwest::Result<Self> {
+        Ok(Self {
+            nexus_key: nexus_key.into(),
+            base_url: BASE_URL.clone(),
+            skip_errors: false,
+            client: reqwest::Client::builder()
+                .timeout(DEFAULT_TIMEOUT)
+                .user_agent(USER_AGENT.as_str())
+                .build()?,
+        })
+    }
+
+    /// Replaces the http client, e.g. to share a connection pool with the rest of an application
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Config of the binary, built from the state set up by [`crate::startup`]
+    pub(crate) fn from_startup() -> Self {
+        Self {
+            nexus_key: VARS.get().expect("set on startup").nexus_key.clone(),
+            base_url: BASE_URL.clone(),
+            skip_errors: SKIP_ERRORS.load(Ordering::Relaxed),
+            client: client().clone(),
+        }
+    }
+
+    fn verify_key(&self) -> Result<(), Error> {
+        if self.nexus_key.is_empty() {
+            return Err(Error::Missing(
+                "Nexus api key missing. Use command 'set' to store private key",
+            ));
+        }
+        Ok(())
+    }
+
+    fn validate_key_endpoint(&self) -> String {
+        format!("{}/v1/users/validate.json", self.base_url)
+    }
+
+    fn graphql_endpoint(&self) -> String {
+        format!("{}/v2/graphql", self.base_url)
+    }
+
+    /// GET request with the api key and the application identification headers Nexus asks for
+    fn get(&self, endpoint: String) -> reqwest::RequestBuilder {
+        self.headers(self.client.get(endpoint))
+    }
+
+    fn headers(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        request
+            .header("accept", "application/json")
+            .header("apikey", &self.nexus_key)
+            .header("Application-Name", env!("CARGO_PKG_NAME"))
+            .header("Application-Version", env!("CARGO_PKG_VERSION"))
+    }
+}
 
 impl Mod {
-    fn get_info_endpoint(&self) -> String {
+    fn get_info_endpoint(&self, base_url: &str) -> String {
         format!(
-            "{NEXUS_BASE_URL}/v1/games/{}/mods/{}.json",
+            "{base_url}/v1/games/{}/mods/{}.json",
             self.domain, self.mod_id
         )
     }
+    fn get_game_endpoint(&self, base_url: &str) -> String {
+        format!("{base_url}/v1/games/{}.json", self.domain)
+    }
     pub fn url(&self) -> String {
         format!(
             "https://www.nexusmods.com/{}/mods/{}",
@@ -30,36 +164,95 @@ async fn abort_and_wait<T: 'static>(tasks: &mut JoinSet<T>) {
     while tasks.join_next().await.is_some() {}
 }
 
-pub async fn update_download_counts(
-    mods: Vec<Mod>,
-    on_remote: bool,
-) -> Result<BTreeMap<String, ModDetails>, Error> {
+pub async fn update_download_counts(mods: Vec<Mod>, skip_write: bool) -> Result<Output, Error> {
     verify_nexus()?;
     verify_added(&mods)?;
 
-    let client = reqwest::Client::new();
+    let output = fetch_counts(&Config::from_startup(), &mods)
+        .await
+        .map_err(|err| match err {
+            Error::Io(err) if err.kind() == ErrorKind::InvalidInput => Error::Io(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("{err}, in: {}", PATHS.input),
+            )),
+            err => err,
+        })?;
+    let output = Output::new(output);
+
+    info!("Retrieved download counts from Nexus Mods");
+
+    if !skip_write {
+        write(output.clone(), &PATHS.output)?;
+        info!(
+            "Download counts saved locally for {} mod(s)",
+            output.mods.len() - 1
+        );
+    }
+
+    Ok(output)
+}
+
+/// Download counts of each of `mods` keyed by their output key, along with the combined counts of
+/// all of them under [`TOTAL_KEY`]. Nothing is printed or written, failures are only logged
+pub async fn fetch_counts(
+    config: &Config,
+    mods: &[Mod],
+) -> Result<BTreeMap<String, ModDetails>, Error> {
+    config.verify_key()?;
+
+    let config = Arc::new(config.clone());
     let mut tasks = JoinSet::new();
     let mut total = ModDetails::total();
+    let permits = Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS));
+    let game_names = Arc::new(
+        mods.iter()
+            .map(|details| (details.domain.clone(), OnceCell::new()))
+            .collect::<HashMap<_, _>>(),
+    );
 
-    for descriptor in mods.into_iter() {
-        tasks.spawn(try_get_info(descriptor, client.clone()));
+    for descriptor in mods.iter().cloned() {
+        let permits = Arc::clone(&permits);
+        let game_names = Arc::clone(&game_names);
+        let config = Arc::clone(&config);
+        tasks.spawn(async move {
+            let _permit = permits
+                .acquire_owned()
+                .await
+                .expect("semaphore is never closed");
+            let game_name =
+                game_names[&descriptor.domain].get_or_init(|| get_game_name(&config, &descriptor));
+            let (game_name, info) =
+                tokio::join!(game_name, try_get_info(&config, descriptor.clone()));
+            let info = info.map(|mut info| {
+                info.game = game_name.clone();
+                (descriptor.key.clone(), info)
+            });
+            (descriptor, info)
+        });
     }
 
+    let skip_errors = config.skip_errors;
     let mut output = BTreeMap::new();
+    let mut counted = HashSet::new();
+    let mut failed = Vec::new();
 
     while let Some(res) = tasks.join_next().await {
         match res {
-            Ok(Ok(data)) => {
-                total.add(&data);
-                if let Some(dup) = output.insert(data.uid.to_string(), data) {
+            Ok((_, Ok((key, data)))) => {
+                if counted.insert(data.uid) {
+                    total.add(&data);
+                }
+                let key = key.unwrap_or_else(|| data.uid.to_string());
+                if let Some(dup) = output.insert(key, data) {
                     abort_and_wait(&mut tasks).await;
                     return Err(Error::Io(io::Error::new(
                         ErrorKind::InvalidInput,
-                        format!("duplicate tracked mod: {}, in: {}", dup.name, PATHS.input),
+                        format!("duplicate tracked mod: {}", dup.name),
                     )));
                 }
             }
-            Ok(Err(err)) => {
+            Ok((descriptor, Err(err))) if skip_errors => failed.push((descriptor, err)),
+            Ok((_, Err(err))) => {
                 abort_and_wait(&mut tasks).await;
                 return Err(err);
             }
@@ -70,36 +263,402 @@ pub async fn update_download_counts(
         }
     }
 
+    if !failed.is_empty() {
+        for (descriptor, err) in failed.iter() {
+            warn!("Skipped: {}, {err}", descriptor.url());
+        }
+        warn!(
+            "Download counts for {} of {} mod(s) could not be retrieved",
+            failed.len(),
+            failed.len() + output.len()
+        );
+        if output.is_empty() {
+            return Err(failed.pop().expect("not empty").1);
+        }
+    }
+
     output.insert(TOTAL_KEY.to_string(), total);
+    output
+        .values_mut()
+        .for_each(ModDetails::set_downloads_combined);
 
-    println!("Retrieved download counts from Nexus Mods");
+    Ok(output)
+}
 
-    if !on_remote {
-        write(output.clone(), &PATHS.output)?;
-        println!(
-            "Download counts saved locally for {} mod(s)",
-            output.len() - 1
+async fn send_with_retry(
+    mut request: reqwest::RequestBuilder,
+) -> reqwest::Result<reqwest::Response> {
+    let mut attempt = 0;
+    loop {
+        let retry_request = request.try_clone().expect("request body is not a stream");
+        match request.send().await {
+            Ok(response) if response.status().is_server_error() && attempt < MAX_RETRIES => (),
+            Err(err) if (err.is_connect() || err.is_timeout()) && attempt < MAX_RETRIES => (),
+            res => return res,
+        }
+        let delay = RETRY_BASE_DELAY * 2_u32.pow(attempt);
+        debug!(
+            "Request failed, retrying in {delay:?} [attempt {}/{MAX_RETRIES}]",
+            attempt + 1
         );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+        request = retry_request;
     }
+}
 
-    Ok(output)
+/// Fetches the details of a single mod, returns `None` if no Nexus api key is set
+pub async fn get_mod_details(config: &Config, details: &Mod) -> Option<Result<ModDetails, Error>> {
+    if config.nexus_key.is_empty() {
+        return None;
+    }
+    Some(try_get_info(config, details.clone()).await)
 }
 
-async fn try_get_info(details: Mod, client: reqwest::Client) -> Result<ModDetails, Error> {
-    let server_response = client
-        .get(details.get_info_endpoint())
-        .header("accept", "application/json")
-        .header("apikey", &VARS.get().expect("set on startup").nexus_key)
-        .send()
-        .await?;
+/// Page size used when listing an author's mods, the largest the Nexus graphql api allows
+const AUTHOR_MODS_PAGE_SIZE: usize = 100;
+
+/// Ids of every mod `author` has uploaded for the game `domain`. The v1 api has no endpoint to list
+/// the mods of a user, so this goes through the v2 graphql api
+pub async fn get_author_mods(
+    config: &Config,
+    domain: &str,
+    author: &str,
+) -> Result<Vec<usize>, Error> {
+    const QUERY: &str = "query AuthorMods($filter: ModsFilter, $count: Int, $offset: Int) { \
+        mods(filter: $filter, count: $count, offset: $offset) { totalCount nodes { modId } } }";
+
+    #[derive(Deserialize)]
+    struct Response {
+        data: Option<Data>,
+        #[serde(default)]
+        errors: Vec<GraphqlError>,
+    }
+
+    #[derive(Deserialize)]
+    struct Data {
+        mods: ModPage,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ModPage {
+        total_count: usize,
+        nodes: Vec<Node>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Node {
+        mod_id: usize,
+    }
+
+    #[derive(Deserialize)]
+    struct GraphqlError {
+        message: String,
+    }
+
+    config.verify_key()?;
+
+    let filter = serde_json::json!({
+        "gameDomainName": [{ "value": domain, "op": "EQUALS" }],
+        "uploader": [{ "value": author, "op": "EQUALS" }],
+    });
+
+    let mut mod_ids = Vec::new();
+    loop {
+        let body = serde_json::json!({
+            "query": QUERY,
+            "variables": {
+                "filter": filter,
+                "count": AUTHOR_MODS_PAGE_SIZE,
+                "offset": mod_ids.len(),
+            },
+        });
+        let request = config
+            .headers(config.client.post(config.graphql_endpoint()))
+            .json(&body);
+        let server_response = send_with_retry(request).await?;
+
+        if server_response.status() != OK_RESPONSE {
+            return Err(Error::bad_nexus_response(server_response).await);
+        }
+
+        let response = server_response.json::<Response>().await?;
+        let page = match (response.data, response.errors.into_iter().next()) {
+            (_, Some(err)) => {
+                return Err(Error::InvalidResponse(format!(
+                    "Nexus could not list the mods of {author}: {}",
+                    err.message
+                )))
+            }
+            (Some(data), None) => data.mods,
+            (None, None) => {
+                return Err(Error::InvalidResponse(String::from(
+                    "Nexus response did not contain a list of mods",
+                )))
+            }
+        };
+
+        let page_len = page.nodes.len();
+        mod_ids.extend(page.nodes.into_iter().map(|node| node.mod_id));
+        debug!(
+            "Found {}/{} mods of {author}",
+            mod_ids.len(),
+            page.total_count
+        );
+        if page_len == 0 || mod_ids.len() >= page.total_count {
+            return Ok(mod_ids);
+        }
+    }
+}
+
+/// Checks that the stored Nexus api key is accepted by Nexus
+pub async fn validate_key(config: &Config) -> Result<(), Error> {
+    config.verify_key()?;
+
+    let server_response = send_with_retry(config.get(config.validate_key_endpoint())).await?;
+
+    if server_response.status() != OK_RESPONSE {
+        return Err(Error::bad_nexus_response(server_response).await);
+    }
+    Ok(())
+}
+
+/// Display name of the game the mod is made for, falls back to an empty string if it can not be
+/// retrieved as it is only used to improve readability of badges
+async fn get_game_name(config: &Config, details: &Mod) -> String {
+    #[derive(Deserialize)]
+    struct GameInfo {
+        name: String,
+    }
+
+    match send_with_retry(config.get(details.get_game_endpoint(&config.base_url))).await {
+        Ok(response) if response.status() == OK_RESPONSE => response
+            .json::<GameInfo>()
+            .await
+            .map(|game| game.name)
+            .unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+/// Only the integer seconds form of `Retry-After` is read, an HTTP-date value is treated the same
+/// as a missing header
+fn retry_after(response: &reqwest::Response) -> Duration {
+    let headers = response.headers();
+    headers
+        .get(RETRY_AFTER)
+        .and_then(|val| val.to_str().ok())
+        .and_then(|secs| secs.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .or_else(|| rate_limit_reset(headers, unix_timestamp()))
+        .unwrap_or(DEFAULT_RATE_LIMIT_WAIT)
+}
+
+/// Time until every exhausted Nexus rate limit has reset, `None` when no bucket is reported as
+/// exhausted or its reset time can not be read
+fn rate_limit_reset(headers: &HeaderMap, now: u64) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|val| val.to_str().ok());
+    RATE_LIMIT_BUCKETS
+        .iter()
+        .filter(|(remaining, _)| {
+            header(remaining).and_then(|n| n.trim().parse::<u64>().ok()) == Some(0)
+        })
+        .filter_map(|(_, reset)| parse_reset_time(header(reset)?))
+        .max()
+        .map(|reset| Duration::from_secs(reset.saturating_sub(now).max(1)))
+}
+
+/// Parses reset times as sent by Nexus, `YYYY-MM-DDTHH:MM:SS+00:00` for the hourly limit and
+/// `YYYY-MM-DD HH:MM:SS +0000` for the daily limit, into seconds since the unix epoch
+fn parse_reset_time(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let date = parse_utc_date(value.get(..10)?)?;
+    if !matches!(value.get(10..11)?, "T" | " ") {
+        return None;
+    }
+
+    let mut time = value.get(11..19)?.splitn(3, ':');
+    let mut next = |max: u64| time.next()?.parse::<u64>().ok().filter(|n| *n <= max);
+    let secs = next(23)? * 3600 + next(59)? * 60 + next(59)?;
+
+    let offset = value[19..]
+        .trim_start_matches(|c: char| c == '.' || c.is_ascii_digit())
+        .trim();
+    let offset_secs = match offset {
+        "" | "Z" => 0,
+        _ => {
+            let (sign, digits) = offset.split_at_checked(1)?;
+            let digits = digits.replace(':', "");
+            if digits.len() != 4 {
+                return None;
+            }
+            let hours = digits[..2].parse::<i64>().ok()?;
+            let mins = digits[2..].parse::<i64>().ok()?;
+            match sign {
+                "+" => hours * 3600 + mins * 60,
+                "-" => -(hours * 3600 + mins * 60),
+                _ => return None,
+            }
+        }
+    };
+
+    u64::try_from((date + secs) as i64 - offset_secs).ok()
+}
+
+async fn try_get_info(config: &Config, details: Mod) -> Result<ModDetails, Error> {
+    debug!("Requesting details of: {}", details.url());
+    let request = config.get(details.get_info_endpoint(&config.base_url));
+    let retry_request = request.try_clone().expect("request body is not a stream");
+    let mut server_response = send_with_retry(request).await?;
+
+    if server_response.status() == TOO_MANY_REQUESTS_RESPONSE {
+        let retry_after = retry_after(&server_response);
+        if retry_after > MAX_RATE_LIMIT_WAIT {
+            return Err(Error::RateLimited { retry_after });
+        }
+        warn!(
+            "Nexus api rate limit reached, retrying in {}s",
+            retry_after.as_secs()
+        );
+        tokio::time::sleep(retry_after).await;
+        server_response = send_with_retry(retry_request).await?;
+
+        if server_response.status() == TOO_MANY_REQUESTS_RESPONSE {
+            return Err(Error::RateLimited {
+                retry_after: self::retry_after(&server_response),
+            });
+        }
+    }
+
+    if server_response.status() == NOT_FOUND_RESPONSE {
+        return Err(Error::NotFound(details.url()));
+    }
+
+    // An invalid api key is a 401, so a 403 means access to this mod in particular was refused
+    if server_response.status() == FORBIDDEN_RESPONSE {
+        #[derive(Deserialize)]
+        struct ErrorBody {
+            message: String,
+        }
+
+        let reason = server_response
+            .json::<ErrorBody>()
+            .await
+            .ok()
+            .map(|body| body.message)
+            .filter(|message| !message.is_empty());
+        return Err(Error::Restricted {
+            url: details.url(),
+            reason,
+        });
+    }
 
     if server_response.status() != OK_RESPONSE {
-        return Err(Error::BadResponse(server_response.text().await?));
+        return Err(Error::bad_nexus_response(server_response).await);
     }
 
     server_response
         .json::<ModDetails>()
         .await
-        .map(|output| output.add_url(&details))
+        .map(|output| output.add_details(&details))
         .map_err(Error::from)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    const NOW: u64 = 1_700_000_000; // 2023-11-14 22:13:20 UTC
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        pairs
+            .iter()
+            .map(|(name, val)| (*name, HeaderValue::from_static(val)))
+            .fold(HeaderMap::new(), |mut map, (name, val)| {
+                map.insert(name, val);
+                map
+            })
+    }
+
+    #[test]
+    fn reset_time_formats() {
+        assert_eq!(
+            parse_reset_time("2023-11-14T23:00:00+00:00"),
+            Some(1_700_002_800)
+        );
+        assert_eq!(
+            parse_reset_time("2023-11-15 00:00:00 +0000"),
+            Some(1_700_006_400)
+        );
+        assert_eq!(
+            parse_reset_time("2023-11-14T23:00:00Z"),
+            Some(1_700_002_800)
+        );
+        assert_eq!(
+            parse_reset_time("2023-11-15T01:00:00+02:00"),
+            Some(1_700_002_800)
+        );
+        assert_eq!(
+            parse_reset_time("2023-11-14T23:00:00.000+00:00"),
+            Some(1_700_002_800)
+        );
+        assert_eq!(parse_reset_time("2023-11-14"), None);
+        assert_eq!(parse_reset_time("2023-11-14T25:00:00+00:00"), None);
+        assert_eq!(parse_reset_time("2023-11-14T23:00:00 UTC"), None);
+    }
+
+    #[test]
+    fn waits_for_exhausted_bucket() {
+        let hourly = headers(&[
+            ("x-rl-hourly-remaining", "0"),
+            ("x-rl-hourly-reset", "2023-11-14T23:00:00+00:00"),
+            ("x-rl-daily-remaining", "1200"),
+            ("x-rl-daily-reset", "2023-11-15 00:00:00 +0000"),
+        ]);
+        assert_eq!(
+            rate_limit_reset(&hourly, NOW),
+            Some(Duration::from_secs(2800))
+        );
+
+        let daily = headers(&[
+            ("x-rl-hourly-remaining", "12"),
+            ("x-rl-hourly-reset", "2023-11-14T23:00:00+00:00"),
+            ("x-rl-daily-remaining", "0"),
+            ("x-rl-daily-reset", "2023-11-15 00:00:00 +0000"),
+        ]);
+        let wait = rate_limit_reset(&daily, NOW).unwrap();
+        assert_eq!(wait, Duration::from_secs(6400));
+        assert!(
+            wait > MAX_RATE_LIMIT_WAIT,
+            "daily resets are reported, not slept on"
+        );
+
+        let both = headers(&[
+            ("x-rl-hourly-remaining", "0"),
+            ("x-rl-hourly-reset", "2023-11-14T23:00:00+00:00"),
+            ("x-rl-daily-remaining", "0"),
+            ("x-rl-daily-reset", "2023-11-15 00:00:00 +0000"),
+        ]);
+        let wait = rate_limit_reset(&both, NOW).unwrap();
+        assert_eq!(wait, Duration::from_secs(6400));
+        assert!(wait > MAX_RATE_LIMIT_WAIT);
+    }
+
+    #[test]
+    fn no_exhausted_bucket() {
+        assert_eq!(rate_limit_reset(&HeaderMap::new(), NOW), None);
+        let remaining = headers(&[
+            ("x-rl-hourly-remaining", "5"),
+            ("x-rl-hourly-reset", "2023-11-14T23:00:00+00:00"),
+        ]);
+        assert_eq!(rate_limit_reset(&remaining, NOW), None);
+        let past = headers(&[
+            ("x-rl-hourly-remaining", "0"),
+            ("x-rl-hourly-reset", "2023-11-14T22:00:00+00:00"),
+        ]);
+        assert_eq!(rate_limit_reset(&past, NOW), Some(Duration::from_secs(1)));
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{
+    models::{cli::Backend, error::Error, json_data::GistResponse},
+    services::{
+        git::{create_remote, delete_remote, get_remote, update_remote},
+        gitlab::{create_snippet, delete_snippet, get_snippet, update_snippet},
+        http::{delete_endpoint, get_endpoint, put_endpoint},
+    },
+};
+
+/// Operations on the json endpoint that badges read download counts from. Every backend reports
+/// the endpoint as a [`GistResponse`] so callers do not need to know which service hosts it
+pub trait RemoteStore {
+    fn create(
+        self,
+        content: String,
+    ) -> impl std::future::Future<Output = Result<GistResponse, Error>> + Send;
+    fn get(self) -> impl std::future::Future<Output = Result<GistResponse, Error>> + Send;
+    fn update(
+        self,
+        content: String,
+    ) -> impl std::future::Future<Output = Result<GistResponse, Error>> + Send;
+    fn delete(self) -> impl std::future::Future<Output = Result<(), Error>> + Send;
+}
+
+impl RemoteStore for Backend {
+    async fn create(self, content: String) -> Result<GistResponse, Error> {
+        match self {
+            Backend::Github => create_remote(content).await,
+            Backend::Gitlab => create_snippet(content).await,
+            Backend::Http => put_endpoint(content).await,
+        }
+    }
+
+    async fn get(self) -> Result<GistResponse, Error> {
+        match self {
+            Backend::Github => get_remote().await,
+            Backend::Gitlab => get_snippet().await,
+            Backend::Http => get_endpoint().await,
+        }
+    }
+
+    async fn update(self, content: String) -> Result<GistResponse, Error> {
+        match self {
+            Backend::Github => update_remote(content).await,
+            Backend::Gitlab => update_snippet(content).await,
+            Backend::Http => put_endpoint(content).await,
+        }
+    }
+
+    async fn delete(self) -> Result<(), Error> {
+        match self {
+            Backend::Github => delete_remote().await,
+            Backend::Gitlab => delete_snippet().await,
+            Backend::Http => delete_endpoint().await,
+        }
+    }
+}